@@ -0,0 +1,345 @@
+use crate::config::Config;
+use crate::diff::{diff_configs, DiffEntry};
+use log::{info, warn};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::{Error, ErrorKind};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+pub const DEFAULT_BACKUP_RETENTION: usize = 5;
+
+fn file_name(path: &Path) -> Result<String, Error> {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("{path:?} is not a file")))
+}
+
+//Backups of a config sit next to it, named <config>.<timestamp>.bak, returned oldest first
+pub fn list_backups(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    let name = file_name(path)?;
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut backups = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry_path = entry?.path();
+        let entry_name = file_name(&entry_path)?;
+        if entry_name.starts_with(&format!("{name}.")) && entry_name.ends_with(".bak") {
+            backups.push(path.with_file_name(entry_name));
+        }
+    }
+    //The timestamps sort lexically in time order, backups of the same millisecond by their counter
+    backups.sort_by_key(|backup| backup_order(backup));
+    Ok(backups)
+}
+
+fn backup_order(backup: &Path) -> (String, u32) {
+    let name = backup
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = name.strip_suffix(".bak").unwrap_or(&name);
+    match stem
+        .rsplit_once('-')
+        .and_then(|(base, counter)| Some((base, counter.parse().ok()?)))
+    {
+        Some((base, counter)) => (base.to_string(), counter),
+        None => (stem.to_string(), 0),
+    }
+}
+
+//Creates the backup with create, named <config>.<timestamp>.bak or, when a backup was already taken
+//in the same millisecond, <config>.<timestamp>-<counter>.bak. create must fail if the path exists
+fn create_backup<T>(
+    path: &Path,
+    create: impl Fn(&Path) -> Result<T, Error>,
+) -> Result<(PathBuf, T), Error> {
+    let now = OffsetDateTime::now_utc();
+    let stem = format!(
+        "{}.{:04}{:02}{:02}T{:02}{:02}{:02}.{:03}Z",
+        file_name(path)?,
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        now.millisecond()
+    );
+    let mut counter = 0;
+    loop {
+        let backup_path = if counter == 0 {
+            path.with_file_name(format!("{stem}.bak"))
+        } else {
+            path.with_file_name(format!("{stem}-{counter}.bak"))
+        };
+        match create(&backup_path) {
+            Ok(created) => return Ok((backup_path, created)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => counter += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn prune_backups(path: &Path, retention: usize) -> Result<(), Error> {
+    let backups = list_backups(path)?;
+    if backups.len() > retention {
+        for old in &backups[..backups.len() - retention] {
+            let removed = if old.is_dir() {
+                fs::remove_dir_all(old)
+            } else {
+                fs::remove_file(old)
+            };
+            if let Err(e) = removed {
+                warn!("Failed to remove old backup {old:?}: {e:?}");
+            }
+        }
+    }
+    Ok(())
+}
+
+//Copies the config to a new timestamped backup and removes the oldest backups beyond retention
+pub fn backup_config(path: &Path, retention: usize) -> Result<Option<PathBuf>, Error> {
+    if retention == 0 || !path.exists() {
+        return Ok(None);
+    }
+    let mut config = fs::File::open(path)?;
+    //The backup holds the same private keys, so it is never readable by others, not even while copying
+    let (backup_path, mut backup) = create_backup(path, |backup_path| {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        options.open(backup_path)
+    })?;
+    if let Err(e) = io::copy(&mut config, &mut backup).and_then(|_| backup.sync_all()) {
+        let _ = fs::remove_file(&backup_path);
+        return Err(e);
+    }
+    prune_backups(path, retention)?;
+    Ok(Some(backup_path))
+}
+
+//Same as backup_config for a whole directory such as the ssl root, file permissions are kept
+pub fn backup_directory(path: &Path, retention: usize) -> Result<Option<PathBuf>, Error> {
+    if retention == 0 || !path.is_dir() {
+        return Ok(None);
+    }
+    let (backup_path, _) = create_backup(path, |backup_path| {
+        fs::create_dir(backup_path)?;
+        #[cfg(unix)]
+        fs::set_permissions(backup_path, fs::Permissions::from_mode(0o700))?;
+        Ok(())
+    })?;
+    copy_directory(path, &backup_path)?;
+    prune_backups(path, retention)?;
+    Ok(Some(backup_path))
+}
+
+fn copy_directory(from: &Path, to: &Path) -> Result<(), Error> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_directory(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+//Saves the config, first backing up the config it replaces
+pub fn save_config(config: &Config, path: &Path, retention: usize) -> Result<(), Error> {
+    if let Some(backup_path) = backup_config(path, retention)? {
+        info!("Backed up existing config to {backup_path:?}");
+    }
+    config.save_as_yaml(path)
+}
+
+//Differences restoring the backup would make, None when the current config is missing or can not
+//be loaded. That is when a restore is needed most, so it is reported instead of stopping the restore
+pub fn restore_changes(path: &Path, backup_path: &Path) -> Result<Option<Vec<DiffEntry>>, Error> {
+    let backup = Config::try_from(backup_path)?;
+    match Config::try_from(path) {
+        Ok(current) => Ok(Some(diff_configs(&current, &backup))),
+        Err(e) => {
+            warn!("Failed to load the current config at {path:?}, restoring without a diff: {e}");
+            Ok(None)
+        }
+    }
+}
+
+//Replaces the config with a backup, the replaced config is backed up as well so a restore can be undone
+pub fn restore_backup(path: &Path, backup_path: &Path, retention: usize) -> Result<Config, Error> {
+    let config = Config::try_from(backup_path)?;
+    save_config(&config, path, retention)?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save(path: &Path, port: u16, retention: usize) {
+        let config = Config {
+            fullnode_rpc_port: port,
+            ..Default::default()
+        };
+        save_config(&config, path, retention).unwrap();
+    }
+
+    fn backed_up_ports(path: &Path) -> Vec<u16> {
+        list_backups(path)
+            .unwrap()
+            .iter()
+            .map(|b| Config::try_from(b).unwrap().fullnode_rpc_port)
+            .collect()
+    }
+
+    #[test]
+    fn lists_only_backups_of_the_config_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fast_farmer.yaml");
+        for name in [
+            "fast_farmer.yaml.20240102T000000.000Z.bak",
+            "fast_farmer.yaml.20240101T000000.000Z-10.bak",
+            "fast_farmer.yaml.20240101T000000.000Z-2.bak",
+            "fast_farmer.yaml.20240101T000000.000Z.bak",
+            "fast_farmer.yaml",
+            "fast_farmer.yaml.tmp",
+            "other.yaml.20240101T000000.000Z.bak",
+        ] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        assert_eq!(
+            list_backups(&path).unwrap(),
+            vec![
+                dir.path().join("fast_farmer.yaml.20240101T000000.000Z.bak"),
+                dir.path()
+                    .join("fast_farmer.yaml.20240101T000000.000Z-2.bak"),
+                dir.path()
+                    .join("fast_farmer.yaml.20240101T000000.000Z-10.bak"),
+                dir.path().join("fast_farmer.yaml.20240102T000000.000Z.bak"),
+            ]
+        );
+        assert_eq!(
+            list_backups(&dir.path().join("missing/fast_farmer.yaml")).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn keeps_the_newest_backups_within_retention() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fast_farmer.yaml");
+        for port in 1..=5 {
+            save(&path, port, 3);
+        }
+        //The first save had nothing to back up, the current config is not a backup
+        assert_eq!(backed_up_ports(&path), vec![2, 3, 4]);
+        assert_eq!(Config::try_from(&path).unwrap().fullnode_rpc_port, 5);
+        #[cfg(unix)]
+        for backup in list_backups(&path).unwrap() {
+            assert_eq!(
+                fs::metadata(backup).unwrap().permissions().mode() & 0o777,
+                0o600
+            );
+        }
+    }
+
+    #[test]
+    fn backups_of_the_same_millisecond_get_a_counter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fast_farmer.yaml");
+        save(&path, 1, 5);
+        let backups: Vec<PathBuf> = (0..3)
+            .map(|_| backup_config(&path, 5).unwrap().unwrap())
+            .collect();
+        assert_eq!(list_backups(&path).unwrap(), backups);
+        //A taken name is skipped for the next counter
+        let attempts = std::cell::RefCell::new(vec![]);
+        let (backup, _) = create_backup(&path, |candidate| {
+            attempts.borrow_mut().push(candidate.to_path_buf());
+            if attempts.borrow().len() < 3 {
+                Err(Error::from(ErrorKind::AlreadyExists))
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+        let attempts = attempts.into_inner();
+        assert!(backup.to_string_lossy().ends_with("Z-2.bak"));
+        assert!(attempts[1].to_string_lossy().ends_with("Z-1.bak"));
+        assert!(attempts[0].to_string_lossy().ends_with("Z.bak"));
+    }
+
+    #[test]
+    fn zero_retention_disables_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fast_farmer.yaml");
+        save(&path, 1, 0);
+        save(&path, 2, 0);
+        assert_eq!(backup_config(&path, 0).unwrap(), None);
+        assert!(list_backups(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn restore_backs_up_the_replaced_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fast_farmer.yaml");
+        save(&path, 1, 5);
+        save(&path, 2, 5);
+        let backup = list_backups(&path).unwrap().remove(0);
+        let restored = restore_backup(&path, &backup, 5).unwrap();
+        assert_eq!(restored.fullnode_rpc_port, 1);
+        assert_eq!(Config::try_from(&path).unwrap(), restored);
+        assert_eq!(backed_up_ports(&path), vec![1, 2]);
+    }
+
+    #[test]
+    fn restores_over_a_corrupted_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fast_farmer.yaml");
+        save(&path, 1, 5);
+        save(&path, 2, 5);
+        let backup = list_backups(&path).unwrap().remove(0);
+        assert_eq!(restore_changes(&path, &backup).unwrap().unwrap().len(), 1);
+        fs::write(&path, "garbage: [").unwrap();
+        assert_eq!(restore_changes(&path, &backup).unwrap(), None);
+        restore_backup(&path, &backup, 5).unwrap();
+        assert_eq!(Config::try_from(&path).unwrap().fullnode_rpc_port, 1);
+        //The corrupted config is kept as the newest backup
+        let newest = list_backups(&path).unwrap().pop().unwrap();
+        assert_eq!(fs::read_to_string(newest).unwrap(), "garbage: [");
+        fs::remove_file(&path).unwrap();
+        assert_eq!(restore_changes(&path, &backup).unwrap(), None);
+        restore_backup(&path, &backup, 5).unwrap();
+        assert_eq!(Config::try_from(&path).unwrap().fullnode_rpc_port, 1);
+    }
+
+    #[test]
+    fn directory_backups_are_pruned_like_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let ssl = dir.path().join("ssl");
+        fs::create_dir_all(ssl.join("ca")).unwrap();
+        fs::write(ssl.join("ca/private_ca.crt"), "crt").unwrap();
+        let first = backup_directory(&ssl, 1).unwrap().unwrap();
+        let second = backup_directory(&ssl, 1).unwrap().unwrap();
+        assert!(!first.exists());
+        assert_eq!(
+            fs::read_to_string(second.join("ca/private_ca.crt")).unwrap(),
+            "crt"
+        );
+        assert_eq!(list_backups(&ssl).unwrap(), vec![second]);
+    }
+}
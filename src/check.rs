@@ -0,0 +1,232 @@
+use crate::config::Config;
+use crate::rpc::{is_community_node, rpc_client_from_config};
+use crate::{CA_PRIVATE_CRT, PRIVATE_CRT, PRIVATE_KEY};
+use dg_xch_clients::api::full_node::FullnodeAPI;
+use dg_xch_core::protocols::shared::NoCertificateVerification;
+use dg_xch_core::ssl::{
+    generate_ca_signed_cert_data, load_certs, load_certs_from_bytes, load_private_key,
+    load_private_key_from_bytes, CHIA_CA_CRT, CHIA_CA_KEY,
+};
+use rustls::{Certificate, ClientConfig, PrivateKey, ServerName};
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::timeout;
+use tokio_rustls::TlsConnector;
+
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStage {
+    Dns,
+    Tcp,
+    Tls,
+    Rpc,
+}
+impl Display for CheckStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CheckStage::Dns => "DNS",
+            CheckStage::Tcp => "TCP",
+            CheckStage::Tls => "TLS",
+            CheckStage::Rpc => "RPC",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageReport {
+    pub stage: CheckStage,
+    pub endpoint: String,
+    pub passed: bool,
+    pub detail: String,
+}
+impl Display for StageReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}: {}",
+            if self.passed { "ok  " } else { "FAIL" },
+            self.stage,
+            self.endpoint,
+            self.detail
+        )
+    }
+}
+
+//The first failed stage as an error, so callers can stop with the exact cause
+pub fn first_failure(reports: &[StageReport]) -> Result<(), Error> {
+    match reports.iter().find(|r| !r.passed) {
+        Some(report) => Err(Error::new(
+            ErrorKind::ConnectionRefused,
+            format!(
+                "Connectivity check failed at the {} stage for {}: {}",
+                report.stage, report.endpoint, report.detail
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+//The same client certs the farmer presents, generated from the public Chia CA when no ssl root is used
+fn client_certs(config: &Config) -> Result<(Vec<Certificate>, PrivateKey), Error> {
+    match &config.ssl_root_path {
+        Some(ssl_root) if !is_community_node(config) => {
+            let ssl_root = Path::new(ssl_root);
+            for file in [PRIVATE_CRT, PRIVATE_KEY, CA_PRIVATE_CRT] {
+                if !ssl_root.join(file).is_file() {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!("Missing SSL file {:?}", ssl_root.join(file)),
+                    ));
+                }
+            }
+            Ok((
+                load_certs(&ssl_root.join(PRIVATE_CRT).to_string_lossy())?,
+                load_private_key(&ssl_root.join(PRIVATE_KEY).to_string_lossy())?,
+            ))
+        }
+        _ => {
+            let (crt, key) =
+                generate_ca_signed_cert_data(CHIA_CA_CRT.as_bytes(), CHIA_CA_KEY.as_bytes())?;
+            Ok((
+                load_certs_from_bytes(&crt)?,
+                load_private_key_from_bytes(&key)?,
+            ))
+        }
+    }
+}
+
+//Resolves, connects and completes a TLS handshake, recording each stage until one fails
+async fn check_endpoint(
+    host: &str,
+    port: u16,
+    tls: &Result<Arc<ClientConfig>, String>,
+    wait: Duration,
+    reports: &mut Vec<StageReport>,
+) -> bool {
+    let endpoint = format!("{host}:{port}");
+    let mut report = |stage: CheckStage, passed: bool, detail: String| {
+        reports.push(StageReport {
+            stage,
+            endpoint: endpoint.clone(),
+            passed,
+            detail,
+        });
+        passed
+    };
+    let addresses: Vec<SocketAddr> = match timeout(wait, lookup_host((host, port))).await {
+        Ok(Ok(addresses)) => addresses.collect(),
+        Ok(Err(e)) => return report(CheckStage::Dns, false, format!("Failed to resolve: {e}")),
+        Err(_) => return report(CheckStage::Dns, false, "Timed out resolving".to_string()),
+    };
+    if addresses.is_empty() {
+        return report(CheckStage::Dns, false, "No addresses found".to_string());
+    }
+    report(
+        CheckStage::Dns,
+        true,
+        format!(
+            "Resolved to {}",
+            addresses
+                .iter()
+                .map(|a| a.ip().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    );
+    let stream = match timeout(wait, TcpStream::connect(addresses.as_slice())).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => return report(CheckStage::Tcp, false, format!("Failed to connect: {e}")),
+        Err(_) => return report(CheckStage::Tcp, false, "Timed out connecting".to_string()),
+    };
+    let peer = stream
+        .peer_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+    report(CheckStage::Tcp, true, format!("Connected to {peer}"));
+    let tls = match tls {
+        Ok(tls) => tls.clone(),
+        Err(e) => return report(CheckStage::Tls, false, e.clone()),
+    };
+    let server_name = match ServerName::try_from(host) {
+        Ok(server_name) => server_name,
+        Err(e) => return report(CheckStage::Tls, false, format!("Invalid host name: {e}")),
+    };
+    match timeout(wait, TlsConnector::from(tls).connect(server_name, stream)).await {
+        Ok(Ok(stream)) => {
+            let (_, connection) = stream.get_ref();
+            report(
+                CheckStage::Tls,
+                true,
+                format!(
+                    "Handshake completed with {:?}",
+                    connection.protocol_version()
+                ),
+            )
+        }
+        Ok(Err(e)) => report(CheckStage::Tls, false, format!("Handshake failed: {e}")),
+        Err(_) => report(CheckStage::Tls, false, "Timed out in handshake".to_string()),
+    }
+}
+
+//Walks the farming websocket and RPC endpoints through DNS, TCP and TLS, then calls
+//get_blockchain_state. Stages after a failure are skipped
+pub async fn check_connectivity(config: &Config, wait: Duration) -> Vec<StageReport> {
+    let tls = client_certs(config)
+        .and_then(|(certs, key)| {
+            ClientConfig::builder()
+                .with_safe_defaults()
+                .with_custom_certificate_verifier(Arc::new(NoCertificateVerification {}))
+                .with_client_auth_cert(certs, key)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{e:?}")))
+        })
+        .map(Arc::new)
+        .map_err(|e| format!("Failed to load client certs: {e}"));
+    let mut reports = vec![];
+    check_endpoint(
+        &config.fullnode_ws_host,
+        config.fullnode_ws_port,
+        &tls,
+        wait,
+        &mut reports,
+    )
+    .await;
+    if check_endpoint(
+        &config.fullnode_rpc_host,
+        config.fullnode_rpc_port,
+        &tls,
+        wait,
+        &mut reports,
+    )
+    .await
+    {
+        let endpoint = format!("{}:{}", config.fullnode_rpc_host, config.fullnode_rpc_port);
+        let client = rpc_client_from_config(config, &None);
+        let (passed, detail) = match timeout(wait, client.get_blockchain_state()).await {
+            Ok(Ok(state)) => (
+                true,
+                format!(
+                    "get_blockchain_state returned peak height {}",
+                    state
+                        .peak
+                        .map(|p| p.height.to_string())
+                        .unwrap_or_else(|| "unknown".to_string())
+                ),
+            ),
+            Ok(Err(e)) => (false, format!("get_blockchain_state failed: {e}")),
+            Err(_) => (false, "Timed out calling get_blockchain_state".to_string()),
+        };
+        reports.push(StageReport {
+            stage: CheckStage::Rpc,
+            endpoint,
+            passed,
+            detail,
+        });
+    }
+    reports
+}
@@ -0,0 +1,230 @@
+use crate::config::Config;
+use crate::generate::GenerateConfig;
+use crate::{get_ssl_root_path, CA_PRIVATE_CRT};
+use home::home_dir;
+use log::info;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+//Only the parts of a Chia config.yaml needed to prefill a FastFarmer config, everything else is ignored
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ChiaConfig {
+    pub self_hostname: Option<String>,
+    pub selected_network: Option<String>,
+    #[serde(default)]
+    pub full_node: ChiaFullNodeConfig,
+    #[serde(default)]
+    pub farmer: ChiaFarmerConfig,
+    #[serde(default)]
+    pub harvester: ChiaHarvesterConfig,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ChiaFullNodeConfig {
+    pub port: Option<u16>,
+    pub rpc_port: Option<u16>,
+    pub selected_network: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ChiaFarmerConfig {
+    pub xch_target_address: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ChiaHarvesterConfig {
+    pub plot_directories: Option<Vec<String>>,
+}
+
+//CHIA_ROOT when set, like the Chia tools themselves, otherwise ~/.chia/mainnet
+pub fn default_chia_root() -> PathBuf {
+    if let Ok(root) = env::var("CHIA_ROOT") {
+        PathBuf::from(root)
+    } else {
+        home_dir()
+            .unwrap_or(Path::new("/").to_path_buf())
+            .join(".chia/mainnet")
+    }
+}
+
+impl ChiaConfig {
+    pub fn load<P: AsRef<Path>>(chia_root: P) -> Result<Self, Error> {
+        let path = chia_root.as_ref().join("config/config.yaml");
+        let contents = fs::read_to_string(&path).map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!("Failed to read Chia config at {path:?}: {e}"),
+            )
+        })?;
+        serde_yaml::from_str(&contents).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid Chia config at {path:?}: {e}"),
+            )
+        })
+    }
+
+    //The node listens on every interface when bound to a wildcard address, connect to it locally
+    pub fn fullnode_host(&self) -> Option<String> {
+        self.self_hostname.as_ref().map(|host| match host.as_str() {
+            "0.0.0.0" | "::" | "[::]" => "localhost".to_string(),
+            host => host.to_string(),
+        })
+    }
+
+    pub fn network(&self) -> Option<String> {
+        self.full_node
+            .selected_network
+            .clone()
+            .or(self.selected_network.clone())
+    }
+}
+
+//Fills every setting not given on the command line from the Chia config, so only what Chia
+//does not know is prompted for. When merging, the existing config wins and Chia only fills what it lacks.
+//Nothing is written here: when the Chia ssl tree contains the private CA the FastFarmer ssl directory
+//is returned, for the caller to set up like `ssl setup` once the config is certain to be saved
+pub fn prefill_from_chia(
+    gen_settings: &mut GenerateConfig,
+    chia_root: &Path,
+    existing: Option<&Config>,
+) -> Result<Option<PathBuf>, Error> {
+    let chia = ChiaConfig::load(chia_root)?;
+    let merging = existing.is_some();
+    let mut imported = vec![];
+    let mut fill = |name: &'static str, target: &mut Option<String>, value: Option<String>| {
+        if target.is_none() && !merging && value.is_some() {
+            *target = value;
+            imported.push(name);
+        }
+    };
+    fill(
+        "fullnode_ws_host",
+        &mut gen_settings.fullnode_ws_host,
+        chia.fullnode_host(),
+    );
+    fill(
+        "fullnode_rpc_host",
+        &mut gen_settings.fullnode_rpc_host,
+        chia.fullnode_host(),
+    );
+    fill(
+        "payout_address",
+        &mut gen_settings.payout_address,
+        chia.farmer.xch_target_address.clone(),
+    );
+    fill(
+        "selected_network",
+        &mut gen_settings.network,
+        chia.network(),
+    );
+    //Chia's own ssl tree is never used directly, generating certs in it would replace the ones
+    //the Chia farmer uses. Its CA is copied to the FastFarmer ssl directory instead
+    let mut ssl_root = None;
+    if gen_settings.fullnode_ssl.is_none()
+        && !merging
+        && chia_root.join("config/ssl").join(CA_PRIVATE_CRT).exists()
+    {
+        let path = get_ssl_root_path(&Config::default());
+        gen_settings.fullnode_ssl = Some(path.to_string_lossy().to_string());
+        ssl_root = Some(path);
+        imported.push("ssl_root_path");
+    }
+    if gen_settings.fullnode_ws_port.is_none() && !merging && chia.full_node.port.is_some() {
+        gen_settings.fullnode_ws_port = chia.full_node.port;
+        imported.push("fullnode_ws_port");
+    }
+    if gen_settings.fullnode_rpc_port.is_none() && !merging && chia.full_node.rpc_port.is_some() {
+        gen_settings.fullnode_rpc_port = chia.full_node.rpc_port;
+        imported.push("fullnode_rpc_port");
+    }
+    //A merged config without Gigahorse settings has no plot directories of its own to keep
+    if gen_settings.plot_directories.is_none()
+        && existing
+            .and_then(|c| c.harvester_configs.gigahorse.as_ref())
+            .is_none()
+        && chia.harvester.plot_directories.is_some()
+    {
+        gen_settings.plot_directories = chia.harvester.plot_directories;
+        imported.push("plot_directories");
+    }
+    info!(
+        "Imported {} from the Chia config in {chia_root:?}",
+        if imported.is_empty() {
+            "nothing".to_string()
+        } else {
+            imported.join(", ")
+        }
+    );
+    Ok(ssl_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::PAYOUT;
+
+    //A Chia root with a config.yaml and the private CA of its ssl tree
+    fn chia_root(dir: &Path) -> PathBuf {
+        let root = dir.join("chia");
+        fs::create_dir_all(root.join("config/ssl/ca")).unwrap();
+        fs::write(root.join("config/ssl").join(CA_PRIVATE_CRT), "crt").unwrap();
+        fs::write(
+            root.join("config/config.yaml"),
+            format!(
+                "self_hostname: 0.0.0.0\n\
+                 selected_network: mainnet\n\
+                 full_node:\n  port: 8444\n  rpc_port: 8555\n\
+                 farmer:\n  xch_target_address: {PAYOUT}\n\
+                 harvester:\n  plot_directories:\n  - /mnt/plots\n"
+            ),
+        )
+        .unwrap();
+        root
+    }
+
+    #[test]
+    fn fills_only_settings_not_given() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = chia_root(dir.path());
+        let mut gen_settings = GenerateConfig {
+            fullnode_rpc_port: Some(18555),
+            ..Default::default()
+        };
+        let ssl_root = prefill_from_chia(&mut gen_settings, &root, None).unwrap();
+        assert_eq!(gen_settings.fullnode_ws_host.as_deref(), Some("localhost"));
+        assert_eq!(gen_settings.fullnode_ws_port, Some(8444));
+        assert_eq!(gen_settings.fullnode_rpc_port, Some(18555));
+        assert_eq!(gen_settings.payout_address.as_deref(), Some(PAYOUT));
+        assert_eq!(gen_settings.network.as_deref(), Some("mainnet"));
+        assert_eq!(
+            gen_settings.plot_directories,
+            Some(vec!["/mnt/plots".to_string()])
+        );
+        //The FastFarmer ssl directory is only named, setting it up is left to the caller
+        let expected = get_ssl_root_path(&Config::default());
+        assert_eq!(ssl_root, Some(expected.clone()));
+        assert_eq!(
+            gen_settings.fullnode_ssl,
+            Some(expected.to_string_lossy().to_string())
+        );
+    }
+
+    #[test]
+    fn merge_keeps_the_existing_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = chia_root(dir.path());
+        let mut gen_settings = GenerateConfig::default();
+        let ssl_root =
+            prefill_from_chia(&mut gen_settings, &root, Some(&Config::default())).unwrap();
+        assert_eq!(ssl_root, None);
+        assert_eq!(gen_settings.fullnode_ws_host, None);
+        assert_eq!(gen_settings.fullnode_ws_port, None);
+        assert_eq!(gen_settings.payout_address, None);
+        assert_eq!(gen_settings.fullnode_ssl, None);
+        //Config::default() has Gigahorse settings, so its plot directories are kept as well
+        assert_eq!(gen_settings.plot_directories, None);
+    }
+}
@@ -1,8 +1,17 @@
+use crate::keys::redact_secret_key;
+use blst::min_pk::SecretKey;
 use dg_xch_core::blockchain::sized_bytes::Bytes32;
 use dg_xch_core::config::PoolWalletConfig;
+use log::warn;
+use serde_yaml::Value;
+use std::fmt::{Debug, Formatter};
 use std::fs;
+use std::io::Write;
 use std::io::{Error, ErrorKind};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
 
 const fn default_true() -> bool {
     true
@@ -23,13 +32,62 @@ const fn default_none<T>() -> Option<T> {
     None
 }
 
+//A secret key that is wiped from memory when dropped and never shown by Debug
+#[derive(Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct SecretBytes32(Bytes32);
+impl SecretBytes32 {
+    pub fn expose(&self) -> &Bytes32 {
+        &self.0
+    }
+}
+impl From<Bytes32> for SecretBytes32 {
+    fn from(value: Bytes32) -> Self {
+        Self(value)
+    }
+}
+impl From<SecretKey> for SecretBytes32 {
+    fn from(value: SecretKey) -> Self {
+        Self(Bytes32::from(&value))
+    }
+}
+impl Debug for SecretBytes32 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretBytes32(<redacted>)")
+    }
+}
+impl Zeroize for SecretBytes32 {
+    fn zeroize(&mut self) {
+        self.0.bytes.zeroize();
+    }
+}
+impl Drop for SecretBytes32 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct FarmingInfo {
-    pub farmer_secret_key: Bytes32,
+    pub farmer_secret_key: SecretBytes32,
     pub launcher_id: Option<Bytes32>,
-    pub pool_secret_key: Option<Bytes32>,
-    pub owner_secret_key: Option<Bytes32>,
-    pub auth_secret_key: Option<Bytes32>,
+    pub pool_secret_key: Option<SecretBytes32>,
+    pub owner_secret_key: Option<SecretBytes32>,
+    pub auth_secret_key: Option<SecretBytes32>,
+}
+
+//Secrets encrypted with a key derived from a passphrase using Argon2id, sealed with XChaCha20Poly1305.
+//Used for encrypted_farmer_info and exported key bundles
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EncryptedSecrets {
+    pub kdf: String,
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+    pub salt: String,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -111,6 +169,8 @@ pub struct Config {
     pub fullnode_rpc_host: String,
     pub fullnode_rpc_port: u16,
     pub farmer_info: Vec<FarmingInfo>,
+    #[serde(default = "default_none", skip_serializing_if = "Option::is_none")]
+    pub encrypted_farmer_info: Option<EncryptedSecrets>,
     pub pool_info: Vec<PoolWalletConfig>,
     pub payout_address: String,
     pub harvester_configs: HarvesterConfig,
@@ -118,12 +178,73 @@ pub struct Config {
 }
 impl Config {
     pub fn save_as_yaml<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
-        fs::write(
-            path.as_ref(),
-            serde_yaml::to_string(&self)
-                .map_err(|e| Error::new(ErrorKind::Other, format!("{:?}", e)))?,
-        )
+        //The YAML holds every secret key in plain hex, wipe it once written
+        let yaml = Zeroizing::new(
+            serde_yaml::to_string(&self).map_err(|e| Error::other(format!("{:?}", e)))?,
+        );
+        write_private_file(path.as_ref(), yaml.as_bytes())
+    }
+    //Commands that change farmer_info need the plain keys, so they refuse to work on encrypted configs
+    pub fn require_decrypted(&self) -> Result<(), Error> {
+        if self.encrypted_farmer_info.is_some() {
+            Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "The farmer_info of this config is encrypted, decrypt it first",
+            ))
+        } else {
+            Ok(())
+        }
     }
+    //The config with every secret key replaced by its public key fingerprint, safe to share
+    pub fn to_redacted_value(&self) -> Result<Value, Error> {
+        let mut value = serde_yaml::to_value(self).map_err(|e| Error::other(format!("{:?}", e)))?;
+        if let Some(Value::Sequence(entries)) = value.get_mut("farmer_info") {
+            for (entry, info) in entries.iter_mut().zip(&self.farmer_info) {
+                let secrets = [
+                    ("farmer_secret_key", Some(&info.farmer_secret_key)),
+                    ("pool_secret_key", info.pool_secret_key.as_ref()),
+                    ("owner_secret_key", info.owner_secret_key.as_ref()),
+                    ("auth_secret_key", info.auth_secret_key.as_ref()),
+                ];
+                for (field, secret_key) in secrets {
+                    if let (Some(field), Some(secret_key)) = (entry.get_mut(field), secret_key) {
+                        *field = Value::String(redact_secret_key(secret_key));
+                    }
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+//Writes to a temporary file next to the target, syncs it and renames it into place,
+//so an interrupted save never leaves a partially written file behind.
+//Used for every file holding private keys, so on Unix it is only readable by the owner
+pub fn write_private_file(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let prefix = format!(
+        ".{}.",
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    );
+    //The temp name is unique, so concurrent saves never write to the same file, and it is
+    //removed when dropped, so a failed save leaves nothing behind
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix).suffix(".tmp");
+    #[cfg(unix)]
+    builder.permissions(fs::Permissions::from_mode(0o600));
+    let mut file = builder.tempfile_in(dir)?;
+    file.write_all(contents)?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    //Sync the directory so the rename itself survives a crash
+    #[cfg(unix)]
+    fs::File::open(dir)?.sync_all()?;
+    Ok(())
 }
 
 impl Default for Config {
@@ -136,6 +257,7 @@ impl Default for Config {
             fullnode_ws_host: "localhost".to_string(),
             fullnode_ws_port: 8444,
             farmer_info: vec![],
+            encrypted_farmer_info: None,
             pool_info: vec![],
             payout_address: "".to_string(),
             harvester_configs: HarvesterConfig {
@@ -152,8 +274,18 @@ impl Default for Config {
 impl TryFrom<&Path> for Config {
     type Error = Error;
     fn try_from(value: &Path) -> Result<Self, Self::Error> {
-        serde_yaml::from_str::<Config>(&fs::read_to_string(value)?)
-            .map_err(|e| Error::new(ErrorKind::Other, format!("{:?}", e)))
+        #[cfg(unix)]
+        {
+            let mode = fs::metadata(value)?.permissions().mode();
+            if mode & 0o077 != 0 {
+                warn!(
+                    "Config {value:?} contains private keys but has permissions {:o}, run chmod 600 on it",
+                    mode & 0o777
+                );
+            }
+        }
+        let yaml = Zeroizing::new(fs::read_to_string(value)?);
+        serde_yaml::from_str::<Config>(&yaml).map_err(|e| Error::other(format!("{:?}", e)))
     }
 }
 impl TryFrom<&PathBuf> for Config {
@@ -162,3 +294,37 @@ impl TryFrom<&PathBuf> for Config {
         Self::try_from(value.as_path())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn private_files_leave_no_temp_file_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fast_farmer.yaml");
+        write_private_file(&path, b"first").unwrap();
+        write_private_file(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        #[cfg(unix)]
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert_eq!(file_names(dir.path()), vec!["fast_farmer.yaml"]);
+        //A directory can not be replaced by the rename, the temp file is removed with the error
+        let blocked = dir.path().join("blocked");
+        fs::create_dir_all(blocked.join("inner")).unwrap();
+        assert!(write_private_file(&blocked, b"data").is_err());
+        assert_eq!(file_names(dir.path()), vec!["blocked", "fast_farmer.yaml"]);
+    }
+}
@@ -0,0 +1,525 @@
+use crate::config::{Config, FarmingInfo, GigahorseHarvesterConfig, MetricsConfig, SecretBytes32};
+use crate::keys::{redact_secret_key, secret_key_fingerprint};
+use dg_xch_core::blockchain::sized_bytes::Bytes32;
+use dg_xch_core::config::PoolWalletConfig;
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffEntry {
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+    Changed {
+        path: String,
+        old: String,
+        new: String,
+    },
+}
+impl DiffEntry {
+    pub fn path(&self) -> &str {
+        match self {
+            DiffEntry::Added { path, .. }
+            | DiffEntry::Removed { path, .. }
+            | DiffEntry::Changed { path, .. } => path,
+        }
+    }
+}
+impl Display for DiffEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffEntry::Added { path, value } => write!(f, "+ {path}: {value}"),
+            DiffEntry::Removed { path, value } => write!(f, "- {path}: {value}"),
+            DiffEntry::Changed { path, old, new } => write!(f, "~ {path}: {old} -> {new}"),
+        }
+    }
+}
+
+fn field<T: PartialEq + Debug>(entries: &mut Vec<DiffEntry>, path: &str, old: &T, new: &T) {
+    if old != new {
+        entries.push(DiffEntry::Changed {
+            path: path.to_string(),
+            old: format!("{old:?}"),
+            new: format!("{new:?}"),
+        });
+    }
+}
+
+fn list<T: Ord + Clone + Debug>(entries: &mut Vec<DiffEntry>, path: &str, old: &[T], new: &[T]) {
+    let old: BTreeSet<T> = old.iter().cloned().collect();
+    let new: BTreeSet<T> = new.iter().cloned().collect();
+    for value in new.difference(&old) {
+        entries.push(DiffEntry::Added {
+            path: path.to_string(),
+            value: format!("{value:?}"),
+        });
+    }
+    for value in old.difference(&new) {
+        entries.push(DiffEntry::Removed {
+            path: path.to_string(),
+            value: format!("{value:?}"),
+        });
+    }
+}
+
+fn redact(secret_key: Option<&SecretBytes32>) -> Option<String> {
+    secret_key.map(redact_secret_key)
+}
+
+//farmer_info entries are keyed by launcher id, entries of a key farmed without a PlotNFT by fingerprint
+pub(crate) fn farming_info_path(info: &FarmingInfo) -> String {
+    match &info.launcher_id {
+        Some(launcher_id) => format!("farmer_info[{launcher_id}]"),
+        None => format!(
+            "farmer_info[fingerprint {}]",
+            secret_key_fingerprint(&info.farmer_secret_key)
+        ),
+    }
+}
+
+pub(crate) fn pool_info_path(launcher_id: &Bytes32) -> String {
+    format!("pool_info[{launcher_id}]")
+}
+
+fn farming_info_summary(info: &FarmingInfo) -> String {
+    format!(
+        "farmer key fingerprint {}",
+        secret_key_fingerprint(&info.farmer_secret_key)
+    )
+}
+
+fn diff_farming_info(
+    entries: &mut Vec<DiffEntry>,
+    path: &str,
+    old: &FarmingInfo,
+    new: &FarmingInfo,
+) {
+    field(
+        entries,
+        &format!("{path}.farmer_secret_key"),
+        &redact(Some(&old.farmer_secret_key)),
+        &redact(Some(&new.farmer_secret_key)),
+    );
+    field(
+        entries,
+        &format!("{path}.pool_secret_key"),
+        &redact(old.pool_secret_key.as_ref()),
+        &redact(new.pool_secret_key.as_ref()),
+    );
+    field(
+        entries,
+        &format!("{path}.owner_secret_key"),
+        &redact(old.owner_secret_key.as_ref()),
+        &redact(new.owner_secret_key.as_ref()),
+    );
+    field(
+        entries,
+        &format!("{path}.auth_secret_key"),
+        &redact(old.auth_secret_key.as_ref()),
+        &redact(new.auth_secret_key.as_ref()),
+    );
+}
+
+fn diff_pool_info(
+    entries: &mut Vec<DiffEntry>,
+    path: &str,
+    old: &PoolWalletConfig,
+    new: &PoolWalletConfig,
+) {
+    field(
+        entries,
+        &format!("{path}.pool_url"),
+        &old.pool_url,
+        &new.pool_url,
+    );
+    field(
+        entries,
+        &format!("{path}.target_puzzle_hash"),
+        &old.target_puzzle_hash,
+        &new.target_puzzle_hash,
+    );
+    field(
+        entries,
+        &format!("{path}.payout_instructions"),
+        &old.payout_instructions,
+        &new.payout_instructions,
+    );
+    field(
+        entries,
+        &format!("{path}.p2_singleton_puzzle_hash"),
+        &old.p2_singleton_puzzle_hash,
+        &new.p2_singleton_puzzle_hash,
+    );
+    field(
+        entries,
+        &format!("{path}.owner_public_key"),
+        &old.owner_public_key,
+        &new.owner_public_key,
+    );
+    field(
+        entries,
+        &format!("{path}.difficulty"),
+        &old.difficulty,
+        &new.difficulty,
+    );
+}
+
+fn diff_gigahorse(
+    entries: &mut Vec<DiffEntry>,
+    old: &GigahorseHarvesterConfig,
+    new: &GigahorseHarvesterConfig,
+) {
+    let path = "harvester_configs.gigahorse";
+    list(
+        entries,
+        &format!("{path}.plot_directories"),
+        &old.plot_directories,
+        &new.plot_directories,
+    );
+    field(
+        entries,
+        &format!("{path}.parallel_read"),
+        &old.parallel_read,
+        &new.parallel_read,
+    );
+    field(
+        entries,
+        &format!("{path}.plot_search_depth"),
+        &old.plot_search_depth,
+        &new.plot_search_depth,
+    );
+    field(
+        entries,
+        &format!("{path}.max_cpu_cores"),
+        &old.max_cpu_cores,
+        &new.max_cpu_cores,
+    );
+    field(
+        entries,
+        &format!("{path}.max_cuda_devices"),
+        &old.max_cuda_devices,
+        &new.max_cuda_devices,
+    );
+    field(
+        entries,
+        &format!("{path}.max_opencl_devices"),
+        &old.max_opencl_devices,
+        &new.max_opencl_devices,
+    );
+    field(
+        entries,
+        &format!("{path}.cuda_device_list"),
+        &old.cuda_device_list,
+        &new.cuda_device_list,
+    );
+    field(
+        entries,
+        &format!("{path}.opencl_device_list"),
+        &old.opencl_device_list,
+        &new.opencl_device_list,
+    );
+    field(
+        entries,
+        &format!("{path}.recompute_host"),
+        &old.recompute_host,
+        &new.recompute_host,
+    );
+    field(
+        entries,
+        &format!("{path}.recompute_port"),
+        &old.recompute_port,
+        &new.recompute_port,
+    );
+}
+
+fn diff_metrics(entries: &mut Vec<DiffEntry>, old: &MetricsConfig, new: &MetricsConfig) {
+    field(entries, "metrics.enabled", &old.enabled, &new.enabled);
+    field(entries, "metrics.port", &old.port, &new.port);
+}
+
+fn optional<T>(
+    entries: &mut Vec<DiffEntry>,
+    path: &str,
+    old: &Option<T>,
+    new: &Option<T>,
+    diff: impl FnOnce(&mut Vec<DiffEntry>, &T, &T),
+) {
+    match (old, new) {
+        (Some(old), Some(new)) => diff(entries, old, new),
+        (None, Some(_)) => entries.push(DiffEntry::Added {
+            path: path.to_string(),
+            value: String::from("enabled"),
+        }),
+        (Some(_), None) => entries.push(DiffEntry::Removed {
+            path: path.to_string(),
+            value: String::from("enabled"),
+        }),
+        (None, None) => {}
+    }
+}
+
+//Field level differences between two configs, secret keys are only ever shown as fingerprints
+pub fn diff_configs(old: &Config, new: &Config) -> Vec<DiffEntry> {
+    let mut entries = vec![];
+    field(
+        &mut entries,
+        "selected_network",
+        &old.selected_network,
+        &new.selected_network,
+    );
+    field(
+        &mut entries,
+        "ssl_root_path",
+        &old.ssl_root_path,
+        &new.ssl_root_path,
+    );
+    field(
+        &mut entries,
+        "fullnode_ws_host",
+        &old.fullnode_ws_host,
+        &new.fullnode_ws_host,
+    );
+    field(
+        &mut entries,
+        "fullnode_ws_port",
+        &old.fullnode_ws_port,
+        &new.fullnode_ws_port,
+    );
+    field(
+        &mut entries,
+        "fullnode_rpc_host",
+        &old.fullnode_rpc_host,
+        &new.fullnode_rpc_host,
+    );
+    field(
+        &mut entries,
+        "fullnode_rpc_port",
+        &old.fullnode_rpc_port,
+        &new.fullnode_rpc_port,
+    );
+    field(
+        &mut entries,
+        "payout_address",
+        &old.payout_address,
+        &new.payout_address,
+    );
+    field(
+        &mut entries,
+        "encrypted_farmer_info",
+        &old.encrypted_farmer_info.is_some(),
+        &new.encrypted_farmer_info.is_some(),
+    );
+    for new_info in &new.farmer_info {
+        let path = farming_info_path(new_info);
+        match old
+            .farmer_info
+            .iter()
+            .find(|f| farming_info_path(f) == path)
+        {
+            Some(old_info) => diff_farming_info(&mut entries, &path, old_info, new_info),
+            None => entries.push(DiffEntry::Added {
+                path,
+                value: farming_info_summary(new_info),
+            }),
+        }
+    }
+    for old_info in &old.farmer_info {
+        let path = farming_info_path(old_info);
+        if !new.farmer_info.iter().any(|f| farming_info_path(f) == path) {
+            entries.push(DiffEntry::Removed {
+                path,
+                value: farming_info_summary(old_info),
+            });
+        }
+    }
+    for new_pool in &new.pool_info {
+        let path = pool_info_path(&new_pool.launcher_id);
+        match old
+            .pool_info
+            .iter()
+            .find(|p| p.launcher_id == new_pool.launcher_id)
+        {
+            Some(old_pool) => diff_pool_info(&mut entries, &path, old_pool, new_pool),
+            None => entries.push(DiffEntry::Added {
+                path,
+                value: new_pool.pool_url.clone(),
+            }),
+        }
+    }
+    for old_pool in &old.pool_info {
+        if !new
+            .pool_info
+            .iter()
+            .any(|p| p.launcher_id == old_pool.launcher_id)
+        {
+            entries.push(DiffEntry::Removed {
+                path: pool_info_path(&old_pool.launcher_id),
+                value: old_pool.pool_url.clone(),
+            });
+        }
+    }
+    optional(
+        &mut entries,
+        "harvester_configs.gigahorse",
+        &old.harvester_configs.gigahorse,
+        &new.harvester_configs.gigahorse,
+        diff_gigahorse,
+    );
+    optional(
+        &mut entries,
+        "harvester_configs.druid_garden",
+        &old.harvester_configs.druid_garden,
+        &new.harvester_configs.druid_garden,
+        |entries, old, new| {
+            list(
+                entries,
+                "harvester_configs.druid_garden.plot_directories",
+                &old.plot_directories,
+                &new.plot_directories,
+            )
+        },
+    );
+    optional(
+        &mut entries,
+        "metrics",
+        &old.metrics,
+        &new.metrics,
+        diff_metrics,
+    );
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{bytes32, farming_info, pool, secret_key};
+
+    fn paths(entries: &[DiffEntry]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|e| match e {
+                DiffEntry::Added { path, .. } => format!("+ {path}"),
+                DiffEntry::Removed { path, .. } => format!("- {path}"),
+                DiffEntry::Changed { path, .. } => format!("~ {path}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_configs_have_no_differences() {
+        let config = Config {
+            farmer_info: vec![farming_info(1, Some(10))],
+            pool_info: vec![pool(10)],
+            ..Default::default()
+        };
+        assert!(diff_configs(&config, &config.clone()).is_empty());
+    }
+
+    #[test]
+    fn reports_changed_fields() {
+        let old = Config::default();
+        let new = Config {
+            fullnode_rpc_port: 18555,
+            ssl_root_path: Some("/ssl".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            diff_configs(&old, &new),
+            vec![
+                DiffEntry::Changed {
+                    path: "ssl_root_path".to_string(),
+                    old: "None".to_string(),
+                    new: "Some(\"/ssl\")".to_string(),
+                },
+                DiffEntry::Changed {
+                    path: "fullnode_rpc_port".to_string(),
+                    old: "8555".to_string(),
+                    new: "18555".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_added_and_removed_entries() {
+        let mut old = Config {
+            farmer_info: vec![farming_info(1, Some(10)), farming_info(2, None)],
+            pool_info: vec![pool(10)],
+            ..Default::default()
+        };
+        old.harvester_configs
+            .gigahorse
+            .as_mut()
+            .unwrap()
+            .plot_directories = vec!["/mnt/a".to_string()];
+        let mut new = Config {
+            farmer_info: vec![farming_info(1, Some(10)), farming_info(3, Some(11))],
+            pool_info: vec![
+                PoolWalletConfig {
+                    pool_url: "https://other.example.com".to_string(),
+                    ..pool(10)
+                },
+                pool(11),
+            ],
+            metrics: None,
+            ..Default::default()
+        };
+        new.harvester_configs
+            .gigahorse
+            .as_mut()
+            .unwrap()
+            .plot_directories = vec!["/mnt/b".to_string()];
+        let launcher_10 = bytes32(10);
+        let launcher_11 = bytes32(11);
+        assert_eq!(
+            paths(&diff_configs(&old, &new)),
+            vec![
+                format!("+ farmer_info[{launcher_11}]"),
+                format!(
+                    "- farmer_info[fingerprint {}]",
+                    secret_key_fingerprint(&secret_key(2))
+                ),
+                format!("~ pool_info[{launcher_10}].pool_url"),
+                format!("+ pool_info[{launcher_11}]"),
+                "+ harvester_configs.gigahorse.plot_directories".to_string(),
+                "- harvester_configs.gigahorse.plot_directories".to_string(),
+                "- metrics".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn secret_keys_are_redacted() {
+        let old = Config {
+            farmer_info: vec![farming_info(1, Some(10))],
+            ..Default::default()
+        };
+        let mut changed = farming_info(1, Some(10));
+        changed.pool_secret_key = Some(secret_key(2));
+        changed.owner_secret_key = Some(secret_key(3));
+        let new = Config {
+            farmer_info: vec![changed, farming_info(4, None)],
+            ..Default::default()
+        };
+        let entries = diff_configs(&old, &new);
+        let launcher_10 = bytes32(10);
+        assert_eq!(
+            entries[0],
+            DiffEntry::Changed {
+                path: format!("farmer_info[{launcher_10}].pool_secret_key"),
+                old: format!("Some({:?})", redact_secret_key(&secret_key(1))),
+                new: format!("Some({:?})", redact_secret_key(&secret_key(2))),
+            }
+        );
+        assert_eq!(entries.len(), 3);
+        let printed: String = entries.iter().map(|e| e.to_string()).collect();
+        for byte in 1..=4u8 {
+            assert!(!printed.contains(&hex::encode([byte; 32])));
+        }
+    }
+}
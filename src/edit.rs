@@ -0,0 +1,246 @@
+use crate::backup::save_config;
+use crate::config::{Config, GigahorseHarvesterConfig};
+use crate::prompts::{resolve_fullnode_alias, PromptProvider};
+use dg_xch_core::blockchain::sized_bytes::Bytes32;
+use dg_xch_core::consensus::constants::CONSENSUS_CONSTANTS_MAP;
+use dg_xch_core::ssl::create_all_ssl;
+use dg_xch_keys::parse_payout_address;
+use log::{info, warn};
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+pub struct EditConfig {
+    pub config_path: PathBuf,
+    pub backup_retention: usize,
+    pub fullnode_ws_host: Option<String>,
+    pub fullnode_ws_port: Option<u16>,
+    pub fullnode_rpc_host: Option<String>,
+    pub fullnode_rpc_port: Option<u16>,
+    pub fullnode_ssl: Option<String>,
+    pub network: Option<String>,
+    pub payout_address: Option<String>,
+    pub plot_directories: Option<Vec<String>>,
+}
+
+pub fn edit_config(
+    edit_settings: EditConfig,
+    prompts: &dyn PromptProvider,
+) -> Result<Config, Error> {
+    let mut config = Config::try_from(&edit_settings.config_path)?;
+    if let Some(network) = edit_settings.network {
+        if CONSENSUS_CONSTANTS_MAP.contains_key(&network) {
+            config.selected_network = network;
+        } else {
+            warn!(
+                "Unknown Network {network}, keeping {}",
+                config.selected_network
+            );
+        }
+    }
+    //Values given on the command line are applied directly, everything else is prompted
+    //for using the value from the loaded config as the default
+    let payout_address = if let Some(address) = edit_settings.payout_address {
+        Bytes32::from(parse_payout_address(&address)?).to_string()
+    } else {
+        prompts
+            .payout_address(Some(config.payout_address.clone()))?
+            .to_string()
+    };
+    if payout_address != config.payout_address {
+        //Only update pools that were following the old payout address
+        for pool in config
+            .pool_info
+            .iter_mut()
+            .filter(|p| p.payout_instructions == config.payout_address)
+        {
+            pool.payout_instructions = payout_address.clone();
+        }
+        config.payout_address = payout_address;
+    }
+    config.fullnode_ws_host = if let Some(host) = edit_settings.fullnode_ws_host {
+        resolve_fullnode_alias(&host)
+    } else {
+        prompts.farming_fullnode(Some(config.fullnode_ws_host.clone()))?
+    };
+    config.fullnode_rpc_host = if let Some(host) = edit_settings.fullnode_rpc_host {
+        resolve_fullnode_alias(&host)
+    } else {
+        prompts.rpc_fullnode(Some(config.fullnode_rpc_host.clone()))?
+    };
+    config.fullnode_ws_port = if let Some(port) = edit_settings.fullnode_ws_port {
+        port
+    } else {
+        prompts.farming_port(Some(config.fullnode_ws_port))?
+    };
+    config.fullnode_rpc_port = if let Some(port) = edit_settings.fullnode_rpc_port {
+        port
+    } else {
+        prompts.rpc_port(Some(config.fullnode_rpc_port))?
+    };
+    //The community node needs no certs, but an own RPC node behind it still does
+    config.ssl_root_path = if [&config.fullnode_ws_host, &config.fullnode_rpc_host]
+        .iter()
+        .all(|host| "chia-proxy.evergreenminer-prod.com" == host.as_str())
+    {
+        None
+    } else if let Some(ssl_path) = edit_settings.fullnode_ssl {
+        Some(ssl_path)
+    } else {
+        prompts.ssl_path(config.ssl_root_path.clone())?
+    };
+    let gigahorse = config
+        .harvester_configs
+        .gigahorse
+        .get_or_insert_with(GigahorseHarvesterConfig::default);
+    gigahorse.plot_directories = if let Some(dirs) = edit_settings.plot_directories {
+        dirs
+    } else {
+        prompts.plot_directories(Some(gigahorse.plot_directories.clone()))?
+    };
+    if let Some(ssl_path) = &config.ssl_root_path {
+        create_all_ssl(Path::new(ssl_path), false)?;
+    }
+    save_config(
+        &config,
+        &edit_settings.config_path,
+        edit_settings.backup_retention,
+    )?;
+    info!("Saved Config to {:?}", edit_settings.config_path);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::{MockPrompts, PromptAnswers};
+    use crate::test_util::{pool, PAYOUT};
+    use dg_xch_core::config::PoolWalletConfig;
+    const NEW_PAYOUT: &str = "0x8888888888888888888888888888888888888888888888888888888888888888";
+    const CUSTOM_PAYOUT: &str =
+        "0x9999999999999999999999999999999999999999999999999999999999999999";
+
+    fn edit_settings(config_path: PathBuf) -> EditConfig {
+        EditConfig {
+            config_path,
+            backup_retention: 0,
+            fullnode_ws_host: None,
+            fullnode_ws_port: None,
+            fullnode_rpc_host: None,
+            fullnode_rpc_port: None,
+            fullnode_ssl: None,
+            network: None,
+            payout_address: None,
+            plot_directories: None,
+        }
+    }
+
+    fn write_config(dir: &Path) -> PathBuf {
+        let path = dir.join("fast_farmer.yaml");
+        let config = Config {
+            payout_address: PAYOUT.to_string(),
+            pool_info: vec![
+                pool(0),
+                PoolWalletConfig {
+                    payout_instructions: CUSTOM_PAYOUT.to_string(),
+                    ..pool(1)
+                },
+            ],
+            ..Default::default()
+        };
+        config.save_as_yaml(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn prompts_with_current_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(dir.path());
+        let prompts = MockPrompts::new(PromptAnswers {
+            payout_address: Some(NEW_PAYOUT.to_string()),
+            fullnode_rpc_port: Some(18555),
+            plot_directories: Some(vec!["/mnt/plots".to_string()]),
+            ..Default::default()
+        });
+        let config = edit_config(edit_settings(path.clone()), &prompts).unwrap();
+        assert_eq!(config.payout_address, NEW_PAYOUT);
+        //Only the pool following the old payout address is moved to the new one
+        assert_eq!(config.pool_info[0].payout_instructions, NEW_PAYOUT);
+        assert_eq!(config.pool_info[1].payout_instructions, CUSTOM_PAYOUT);
+        assert_eq!(config.fullnode_ws_host, "localhost");
+        assert_eq!(config.fullnode_ws_port, 8444);
+        assert_eq!(config.fullnode_rpc_port, 18555);
+        assert_eq!(
+            config
+                .harvester_configs
+                .gigahorse
+                .as_ref()
+                .unwrap()
+                .plot_directories,
+            vec!["/mnt/plots".to_string()]
+        );
+        assert_eq!(Config::try_from(&path).unwrap(), config);
+        assert_eq!(
+            prompts.asked(),
+            vec![
+                "payout_address",
+                "fullnode_ws_host",
+                "fullnode_rpc_host",
+                "fullnode_ws_port",
+                "fullnode_rpc_port",
+                "ssl_path",
+                "plot_directories"
+            ]
+        );
+    }
+
+    #[test]
+    fn given_values_are_not_prompted_for() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(dir.path());
+        let prompts = MockPrompts::new(PromptAnswers::default());
+        let config = edit_config(
+            EditConfig {
+                fullnode_ws_host: Some("c".to_string()),
+                fullnode_ws_port: Some(443),
+                fullnode_rpc_host: Some("community".to_string()),
+                fullnode_rpc_port: Some(443),
+                network: Some("not-a-network".to_string()),
+                plot_directories: Some(vec![]),
+                ..edit_settings(path)
+            },
+            &prompts,
+        )
+        .unwrap();
+        assert_eq!(config.selected_network, "mainnet");
+        assert_eq!(
+            config.fullnode_rpc_host,
+            "chia-proxy.evergreenminer-prod.com"
+        );
+        assert_eq!(config.ssl_root_path, None);
+        assert_eq!(config.payout_address, PAYOUT);
+        assert_eq!(prompts.asked(), vec!["payout_address"]);
+    }
+
+    #[test]
+    fn keeps_the_ssl_path_of_an_own_rpc_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(dir.path());
+        let ssl = dir.path().join("ssl").to_string_lossy().to_string();
+        let prompts = MockPrompts::new(PromptAnswers::default());
+        let config = edit_config(
+            EditConfig {
+                fullnode_ws_host: Some("community".to_string()),
+                fullnode_rpc_host: Some("node.example.com".to_string()),
+                fullnode_ws_port: Some(443),
+                fullnode_rpc_port: Some(8555),
+                fullnode_ssl: Some(ssl.clone()),
+                plot_directories: Some(vec![]),
+                ..edit_settings(path)
+            },
+            &prompts,
+        )
+        .unwrap();
+        assert_eq!(config.fullnode_rpc_host, "node.example.com");
+        assert_eq!(config.ssl_root_path, Some(ssl));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::config::{Config, EncryptedSecrets, FarmingInfo};
+use crate::prompts::PromptProvider;
+use crate::secrets::{read_config_passphrase, SecretSource};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use hex::{decode, encode};
+use std::io::{Error, ErrorKind};
+use zeroize::Zeroizing;
+
+const KDF: &str = "argon2id";
+const CIPHER: &str = "xchacha20poly1305";
+//64 MiB, 3 passes, 1 lane. Stored with the ciphertext so they can be raised later
+const MEMORY_COST: u32 = 64 * 1024;
+const TIME_COST: u32 = 3;
+const PARALLELISM: u32 = 1;
+pub(crate) const FARMER_INFO_AAD: &[u8] = b"fast_farmer_config/farmer-info/v1";
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+) -> Result<Zeroizing<[u8; 32]>, Error> {
+    let params = Params::new(memory_cost, time_cost, parallelism, Some(32))
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("Invalid KDF Params: {e}")))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| Error::other(format!("Failed to derive key: {e}")))?;
+    Ok(key)
+}
+
+fn decode_field(what: &str, name: &str, value: &str) -> Result<Vec<u8>, Error> {
+    decode(value).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid {name} in {what}: {e:?}"),
+        )
+    })
+}
+
+//Encrypts with a fresh salt and nonce, the associated data is authenticated but not stored
+pub fn seal(passphrase: &str, plaintext: &[u8], aad: &[u8]) -> Result<EncryptedSecrets, Error> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, MEMORY_COST, TIME_COST, PARALLELISM)?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|e| Error::other(format!("Failed to encrypt: {e}")))?;
+    Ok(EncryptedSecrets {
+        kdf: KDF.to_string(),
+        memory_cost: MEMORY_COST,
+        time_cost: TIME_COST,
+        parallelism: PARALLELISM,
+        salt: encode(salt),
+        cipher: CIPHER.to_string(),
+        nonce: encode(nonce),
+        ciphertext: encode(ciphertext),
+    })
+}
+
+//Decrypts and authenticates, what names the encrypted data in error messages
+pub fn open(
+    encrypted: &EncryptedSecrets,
+    passphrase: &str,
+    aad: &[u8],
+    what: &str,
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    if encrypted.kdf != KDF || encrypted.cipher != CIPHER {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "Unsupported encryption {} with {}",
+                encrypted.cipher, encrypted.kdf
+            ),
+        ));
+    }
+    let key = derive_key(
+        passphrase,
+        &decode_field(what, "salt", &encrypted.salt)?,
+        encrypted.memory_cost,
+        encrypted.time_cost,
+        encrypted.parallelism,
+    )?;
+    let nonce = decode_field(what, "nonce", &encrypted.nonce)?;
+    if nonce.len() != 24 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid nonce in {what}"),
+        ));
+    }
+    let ciphertext = decode_field(what, "ciphertext", &encrypted.ciphertext)?;
+    XChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| {
+            Error::new(
+                ErrorKind::PermissionDenied,
+                format!("Failed to decrypt {what}, wrong passphrase or modified file"),
+            )
+        })
+}
+
+//Moves farmer_info into encrypted_farmer_info, leaving an empty farmer_info in the config
+pub fn encrypt_farmer_info(config: &mut Config, passphrase: &str) -> Result<(), Error> {
+    if config.encrypted_farmer_info.is_some() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            "The farmer_info of this config is already encrypted",
+        ));
+    }
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(&config.farmer_info).map_err(|e| Error::other(format!("{:?}", e)))?,
+    );
+    config.encrypted_farmer_info = Some(seal(passphrase, &plaintext, FARMER_INFO_AAD)?);
+    config.farmer_info = vec![];
+    Ok(())
+}
+
+pub fn decrypt_farmer_info(config: &mut Config, passphrase: &str) -> Result<(), Error> {
+    let encrypted = config.encrypted_farmer_info.as_ref().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "The farmer_info of this config is not encrypted",
+        )
+    })?;
+    let plaintext = open(
+        encrypted,
+        passphrase,
+        FARMER_INFO_AAD,
+        "encrypted_farmer_info",
+    )?;
+    let farmer_info: Vec<FarmingInfo> =
+        serde_json::from_slice(&plaintext).map_err(|e| Error::other(format!("{:?}", e)))?;
+    config.farmer_info.extend(farmer_info);
+    config.encrypted_farmer_info = None;
+    Ok(())
+}
+
+//Decrypts farmer_info in memory when the config is encrypted, used by commands that read the keys
+pub fn decrypt_if_encrypted(
+    config: &mut Config,
+    passphrase: Option<&SecretSource>,
+    prompts: &dyn PromptProvider,
+) -> Result<(), Error> {
+    if config.encrypted_farmer_info.is_some() {
+        let passphrase = read_config_passphrase(passphrase, false, prompts)?;
+        decrypt_farmer_info(config, &passphrase)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::farming_info;
+
+    const AAD: &[u8] = b"test/v1";
+
+    fn assert_rejected(result: Result<Zeroizing<Vec<u8>>, Error>) {
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let encrypted = seal("correct horse", b"secret keys", AAD).unwrap();
+        assert_eq!(encrypted.kdf, KDF);
+        assert_eq!(encrypted.cipher, CIPHER);
+        assert!(!encrypted.ciphertext.contains(&encode(b"secret keys")));
+        let plaintext = open(&encrypted, "correct horse", AAD, "test").unwrap();
+        assert_eq!(plaintext.as_slice(), b"secret keys");
+    }
+
+    #[test]
+    fn seal_uses_a_fresh_salt_and_nonce() {
+        let first = seal("correct horse", b"secret keys", AAD).unwrap();
+        let second = seal("correct horse", b"secret keys", AAD).unwrap();
+        assert_ne!(first.salt, second.salt);
+        assert_ne!(first.nonce, second.nonce);
+        assert_ne!(first.ciphertext, second.ciphertext);
+    }
+
+    #[test]
+    fn open_rejects_wrong_passphrase_and_associated_data() {
+        let encrypted = seal("correct horse", b"secret keys", AAD).unwrap();
+        assert_rejected(open(&encrypted, "wrong horse", AAD, "test"));
+        assert_rejected(open(&encrypted, "correct horse", b"other/v1", "test"));
+    }
+
+    #[test]
+    fn open_rejects_tampering() {
+        let encrypted = seal("correct horse", b"secret keys", AAD).unwrap();
+        let mut ciphertext = decode(&encrypted.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        assert_rejected(open(
+            &EncryptedSecrets {
+                ciphertext: encode(ciphertext),
+                ..encrypted.clone()
+            },
+            "correct horse",
+            AAD,
+            "test",
+        ));
+        let mut nonce = decode(&encrypted.nonce).unwrap();
+        nonce[0] ^= 1;
+        assert_rejected(open(
+            &EncryptedSecrets {
+                nonce: encode(nonce),
+                ..encrypted.clone()
+            },
+            "correct horse",
+            AAD,
+            "test",
+        ));
+        //The stored KDF parameters are not authenticated, but changing them derives another key
+        assert_rejected(open(
+            &EncryptedSecrets {
+                time_cost: TIME_COST - 1,
+                ..encrypted.clone()
+            },
+            "correct horse",
+            AAD,
+            "test",
+        ));
+        let error = open(
+            &EncryptedSecrets {
+                cipher: "aes256gcm".to_string(),
+                ..encrypted
+            },
+            "correct horse",
+            AAD,
+            "test",
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn farmer_info_round_trip() {
+        let farmer_info = vec![farming_info(1, Some(2)), farming_info(3, None)];
+        let mut config = Config {
+            farmer_info: farmer_info.clone(),
+            ..Default::default()
+        };
+        encrypt_farmer_info(&mut config, "correct horse").unwrap();
+        assert!(config.farmer_info.is_empty());
+        assert_eq!(
+            encrypt_farmer_info(&mut config, "correct horse")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::AlreadyExists
+        );
+        let error = decrypt_farmer_info(&mut config, "wrong horse").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+        assert!(config.encrypted_farmer_info.is_some());
+        decrypt_farmer_info(&mut config, "correct horse").unwrap();
+        assert_eq!(config.farmer_info, farmer_info);
+        assert_eq!(config.encrypted_farmer_info, None);
+    }
+}
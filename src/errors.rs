@@ -0,0 +1,57 @@
+use crate::validate::ValidationIssue;
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_MISSING_INPUT: i32 = 3;
+pub const EXIT_INVALID_CONFIG: i32 = 4;
+
+//Returned when running with --non-interactive and required values were not supplied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingInputs {
+    pub fields: Vec<&'static str>,
+}
+impl Display for MissingInputs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Missing required values for non-interactive mode: {}",
+            self.fields.join(", ")
+        )
+    }
+}
+impl std::error::Error for MissingInputs {}
+impl From<MissingInputs> for Error {
+    fn from(value: MissingInputs) -> Self {
+        Error::new(ErrorKind::InvalidInput, value)
+    }
+}
+
+//Returned by validate when the config loads but fails one or more checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidConfig {
+    pub issues: Vec<ValidationIssue>,
+}
+impl Display for InvalidConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Config has {} problem(s):", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n    {issue}")?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for InvalidConfig {}
+impl From<InvalidConfig> for Error {
+    fn from(value: InvalidConfig) -> Self {
+        Error::new(ErrorKind::InvalidData, value)
+    }
+}
+
+pub fn exit_code_for(error: &Error) -> i32 {
+    match error.get_ref() {
+        Some(inner) if inner.is::<MissingInputs>() => EXIT_MISSING_INPUT,
+        Some(inner) if inner.is::<InvalidConfig>() => EXIT_INVALID_CONFIG,
+        _ => EXIT_FAILURE,
+    }
+}
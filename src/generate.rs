@@ -0,0 +1,710 @@
+use crate::backup::save_config;
+use crate::check::{check_connectivity, first_failure, DEFAULT_CHECK_TIMEOUT};
+use crate::chia::prefill_from_chia;
+use crate::config::{Config, FarmingInfo, GigahorseHarvesterConfig};
+use crate::encryption::encrypt_farmer_info;
+use crate::errors::MissingInputs;
+use crate::get_ssl_root_path;
+use crate::keyring::{import_keys, ExportedKeys};
+use crate::keys::{farming_info_for_key, find_owner_keys};
+use crate::merge::{log_config_changes, summarize_changes};
+use crate::plotnft::{
+    find_plotnft_by_launcher_id, pool_wallet_config, scan_for_plotnfts, ScanOptions,
+};
+use crate::prompts::{resolve_fullnode_alias, PromptProvider};
+use crate::rpc::rpc_client_from_config;
+use crate::secrets::{derive_master_key, read_config_passphrase, read_mnemonic, SecretSource};
+use crate::ssl::setup_ssl;
+use blst::min_pk::SecretKey;
+use dg_xch_clients::rpc::full_node::FullnodeClient;
+use dg_xch_core::blockchain::sized_bytes::Bytes32;
+use dg_xch_core::consensus::constants::CONSENSUS_CONSTANTS_MAP;
+use dg_xch_core::plots::PlotNft;
+use dg_xch_core::ssl::create_all_ssl;
+use dg_xch_keys::fingerprint;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct GenerateConfig {
+    pub output_path: Option<PathBuf>,
+    //Every source adds another key, the mnemonic is prompted for when empty
+    pub mnemonics: Vec<SecretSource>,
+    pub passphrase: Option<SecretSource>,
+    pub fullnode_ws_host: Option<String>,
+    pub fullnode_ws_port: Option<u16>,
+    pub fullnode_rpc_host: Option<String>,
+    pub fullnode_rpc_port: Option<u16>,
+    pub fullnode_ssl: Option<String>,
+    pub network: Option<String>,
+    pub launcher_id: Option<Bytes32>,
+    pub payout_address: Option<String>,
+    pub plot_directories: Option<Vec<String>>,
+    pub additional_headers: Option<HashMap<String, String>>,
+    pub non_interactive: bool,
+    pub overwrite: Option<bool>,
+    pub merge: bool,
+    pub scan: ScanOptions,
+    //When set the farmer_info is encrypted with the passphrase from this source before saving
+    pub encrypt: Option<SecretSource>,
+    //Number of backups kept when an existing config is overwritten, 0 disables backups
+    pub backup_retention: usize,
+    //Secret keys supplied directly, no mnemonic is read when set
+    pub keys: Option<ExportedKeys>,
+    //Chia root whose config.yaml prefills every setting not given above
+    pub chia_root: Option<PathBuf>,
+    //Skips the connectivity check run before searching for PlotNFTs
+    pub skip_check: bool,
+}
+
+pub async fn generate_config_from_mnemonic(
+    mut gen_settings: GenerateConfig,
+    prompts: &dyn PromptProvider,
+) -> Result<Config, Error> {
+    //When merging, the existing config is the base and its values become the prompt defaults
+    let existing = match &gen_settings.output_path {
+        Some(op) if gen_settings.merge && op.exists() => {
+            let existing = Config::try_from(op)?;
+            existing.require_decrypted()?;
+            Some(existing)
+        }
+        _ => None,
+    };
+    let chia_ssl_root = match gen_settings.chia_root.clone() {
+        Some(chia_root) => prefill_from_chia(&mut gen_settings, &chia_root, existing.as_ref())?,
+        None => None,
+    };
+    if gen_settings.non_interactive {
+        check_non_interactive_inputs(&gen_settings, existing.as_ref())?;
+    }
+    //Check for Existing Config and prompt for override
+    if let Some(op) = &gen_settings.output_path {
+        if op.exists() && existing.is_none() {
+            let overwrite = if let Some(overwrite) = gen_settings.overwrite {
+                overwrite
+            } else {
+                prompts.overwrite(op)?
+            };
+            if !overwrite {
+                return Err(Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("Not overwriting existing config at {:?}", op),
+                ));
+            }
+        }
+    }
+    //The Chia CA is only copied once nothing stops the config from being written
+    if let (Some(chia_root), Some(ssl_root)) = (&gen_settings.chia_root, &chia_ssl_root) {
+        if let Err(e) = setup_ssl(chia_root, ssl_root) {
+            warn!("Failed to set up SSL from the Chia CA in {chia_root:?}: {e}");
+            gen_settings.fullnode_ssl = None;
+        }
+    }
+    //Prompt the User for Mnemonic to Generate needed Keys, every mnemonic file adds another key
+    let mut master_keys = vec![];
+    for source in &gen_settings.mnemonics {
+        master_keys.push(derive_master_key(
+            &read_mnemonic(source, prompts)?,
+            gen_settings.passphrase.as_ref(),
+            prompts,
+        )?);
+    }
+    if master_keys.is_empty() && gen_settings.keys.is_none() {
+        master_keys.push(derive_master_key(
+            &prompts.mnemonic()?,
+            gen_settings.passphrase.as_ref(),
+            prompts,
+        )?);
+    }
+    let previous_payout_address = existing
+        .as_ref()
+        .map(|c| c.payout_address.clone())
+        .unwrap_or_default();
+    let mut config = prompt_for_config(&gen_settings, existing.as_ref(), prompts)?;
+    //Always check/generate the SSL, this will not overwrite existing files
+    if let Some(ssl_path) = &config.ssl_root_path {
+        create_all_ssl(Path::new(ssl_path), false)?;
+    } else {
+        let ssl_path = get_ssl_root_path(&config);
+        create_all_ssl(&ssl_path, false)?;
+        //A merged config without ssl_root_path keeps using the default directory without naming it
+        if existing.is_none() {
+            config.ssl_root_path = Some(ssl_path.to_string_lossy().to_string());
+        }
+    }
+    //If the RPC connection fails it is likely one of the below:
+    //1. The wrong SSL path was given or the ca files don't match the Fullnode.
+    //2. The self_hostname field in the Chia Fullnode config is not set to 0.0.0.0 and you are trying to connect remotely
+    //3. The Port or Hostname fields are not set up correctly to match the Fullnode, verify which ports the RPC and WS are running on in the Chia config
+    //The connectivity check reports which stage fails before the PlotNFT search does
+    if !gen_settings.skip_check {
+        let reports = check_connectivity(&config, DEFAULT_CHECK_TIMEOUT).await;
+        for report in &reports {
+            info!("{report}");
+        }
+        first_failure(&reports)?;
+    }
+    let client = rpc_client_from_config(&config, &gen_settings.additional_headers);
+    //Without a mnemonic there are no wallet keys to scan with, so only the given PlotNFT is added
+    if let Some(keys) = &gen_settings.keys {
+        let launcher_ids: Vec<Bytes32> = prompts
+            .launcher_id(gen_settings.launcher_id)?
+            .into_iter()
+            .collect();
+        import_keys(
+            &mut config,
+            std::slice::from_ref(keys),
+            &launcher_ids,
+            client.clone(),
+            &previous_payout_address,
+        )
+        .await?;
+    }
+    //The launcher id only applies to the first key, PlotNFTs of other keys are found by scanning
+    let mut launcher_id = gen_settings.launcher_id;
+    for master_key in &master_keys {
+        add_key_to_config(
+            &mut config,
+            master_key,
+            client.clone(),
+            launcher_id.take(),
+            &gen_settings.scan,
+            &previous_payout_address,
+            prompts,
+        )
+        .await?;
+    }
+    //The master keys wipe themselves on drop, release them as soon as farmer_info is built
+    drop(master_keys);
+    //When the mnemonic was typed in, keep asking for more keys until the user is done
+    if gen_settings.mnemonics.is_empty() && gen_settings.keys.is_none() {
+        while prompts.another_key()? {
+            let master_key = derive_master_key(
+                &prompts.mnemonic()?,
+                gen_settings.passphrase.as_ref(),
+                prompts,
+            )?;
+            add_key_to_config(
+                &mut config,
+                &master_key,
+                client.clone(),
+                None,
+                &gen_settings.scan,
+                &previous_payout_address,
+                prompts,
+            )
+            .await?;
+        }
+    }
+    if let Some(source) = &gen_settings.encrypt {
+        let passphrase = read_config_passphrase(Some(source), true, prompts)?;
+        encrypt_farmer_info(&mut config, &passphrase)?;
+    }
+    if let Some(existing) = &existing {
+        log_config_changes(&summarize_changes(existing, &config));
+    }
+    if let Some(op) = &gen_settings.output_path {
+        save_config(&config, op, gen_settings.backup_retention)?;
+    }
+    Ok(config)
+}
+
+//Applies the given settings and prompts for the rest, starting from the existing config when merging.
+//Nothing here touches the network or the disk, so the prompt flow can run on its own
+pub fn prompt_for_config(
+    gen_settings: &GenerateConfig,
+    existing: Option<&Config>,
+    prompts: &dyn PromptProvider,
+) -> Result<Config, Error> {
+    let mut config = existing.cloned().unwrap_or_default();
+    let network = gen_settings
+        .network
+        .clone()
+        .map(|v| {
+            if CONSENSUS_CONSTANTS_MAP.contains_key(&v) {
+                v
+            } else {
+                "mainnet".to_string()
+            }
+        })
+        .unwrap_or(config.selected_network.clone());
+    config.selected_network = network;
+    //Prompt for Payout Address, Will populate farmer and pool reward addresses
+    config.payout_address = prompts
+        .payout_address(
+            gen_settings
+                .payout_address
+                .clone()
+                .or(existing.map(|c| c.payout_address.clone())),
+        )?
+        .to_string();
+    //Prompt for Node to connect the Farming Websocket
+    config.fullnode_ws_host = prompts.farming_fullnode(
+        gen_settings
+            .fullnode_ws_host
+            .clone()
+            .or(existing.map(|c| c.fullnode_ws_host.clone())),
+    )?;
+    //If the User is not using the community node, ask for RPC info.
+    //This is used to update the status of the fullnode and to search for plot_nft info
+    //If using the migrate functions of the farmer this is also where the push_tx call will go
+    //When merging the existing values come first, like the ports below
+    config.fullnode_rpc_host = if let Some(host) = &gen_settings.fullnode_rpc_host {
+        resolve_fullnode_alias(host)
+    } else if let Some(existing) = existing {
+        prompts.rpc_fullnode(Some(existing.fullnode_rpc_host.clone()))?
+    } else if "chia-proxy.evergreenminer-prod.com" == config.fullnode_ws_host {
+        "chia-proxy.evergreenminer-prod.com".to_string()
+    } else {
+        prompts.rpc_fullnode(None)?
+    };
+    //Farming Port, typically 443 for community and 8444 for local node
+    config.fullnode_ws_port = if let Some(port) = gen_settings.fullnode_ws_port {
+        port
+    } else if let Some(existing) = existing {
+        prompts.farming_port(Some(existing.fullnode_ws_port))?
+    } else if "chia-proxy.evergreenminer-prod.com" == config.fullnode_ws_host {
+        443
+    } else if "localhost" == config.fullnode_ws_host {
+        8444
+    } else {
+        prompts.farming_port(None)?
+    };
+    //RPC Port, typically 443 for community and 8555 for local node
+    config.fullnode_rpc_port = if let Some(port) = gen_settings.fullnode_rpc_port {
+        port
+    } else if let Some(existing) = existing {
+        prompts.rpc_port(Some(existing.fullnode_rpc_port))?
+    } else if "chia-proxy.evergreenminer-prod.com" == config.fullnode_rpc_host {
+        443
+    } else if "localhost" == config.fullnode_rpc_host {
+        8555
+    } else {
+        prompts.rpc_port(None)?
+    };
+    //For community node this can be left blank as it will generate the required certs.
+    //For local hosted nodes, it is recommended to run `ff_config ssl setup` which copies the "ssl/ca"
+    //from your chia install to a FastFarmer folder and generates the certs from it. This will allow
+    //FastFarmer to connect without conflicting with the Chia Farmer that runs with the GUI or any other farmers.
+    config.ssl_root_path = if let Some(existing) = existing {
+        prompts.ssl_path(
+            gen_settings
+                .fullnode_ssl
+                .clone()
+                .or(existing.ssl_root_path.clone()),
+        )?
+    } else if "chia-proxy.evergreenminer-prod.com" == config.fullnode_ws_host {
+        None
+    } else {
+        prompts.ssl_path(gen_settings.fullnode_ssl.clone())?
+    };
+    //This tool is used to generate GigahorseCongigs.
+    //For regular DruidGarden config please use the Open Source version of FastFarmer
+    //When merging the existing Gigahorse settings are kept and only the directories are updated
+    let existing_gigahorse = existing.and_then(|c| c.harvester_configs.gigahorse.clone());
+    let plot_directories = if let Some(dirs) = &gen_settings.plot_directories {
+        dirs.clone()
+    } else {
+        prompts.plot_directories(
+            existing_gigahorse
+                .as_ref()
+                .map(|g| g.plot_directories.clone()),
+        )?
+    };
+    config.harvester_configs.gigahorse = Some(GigahorseHarvesterConfig {
+        plot_directories,
+        ..existing_gigahorse.unwrap_or(GigahorseHarvesterConfig {
+            plot_directories: vec![],
+            parallel_read: true,
+            plot_search_depth: 2,
+            max_cpu_cores: -1,
+            max_cuda_devices: -1,
+            max_opencl_devices: -1,
+            cuda_device_list: vec![],
+            opencl_device_list: vec![],
+            recompute_host: "".to_string(),
+            recompute_port: 0,
+        })
+    });
+    Ok(config)
+}
+
+//Discovers the PlotNFTs of a key and adds its farmer and pool entries to the config,
+//entries for other keys are left untouched
+pub async fn add_key_to_config(
+    config: &mut Config,
+    master_key: &SecretKey,
+    client: Arc<FullnodeClient>,
+    launcher_id: Option<Bytes32>,
+    scan: &ScanOptions,
+    previous_payout_address: &str,
+    prompts: &dyn PromptProvider,
+) -> Result<(), Error> {
+    info!(
+        "Adding key with fingerprint {}",
+        fingerprint(&master_key.sk_to_pk())
+    );
+    //Depending on how many "Claims" have happened with your PlotNFT this process can take some time.
+    let launcher_id = prompts.launcher_id(launcher_id)?;
+    let mut plotnfts = if let Some(launcher_id) = launcher_id {
+        vec![find_plotnft_by_launcher_id(client.clone(), &launcher_id).await?]
+    } else {
+        scan_for_plotnfts(client.clone(), master_key, scan).await?
+    };
+    if plotnfts.is_empty() {
+        warn!("No PlotNFT Found");
+    } else if plotnfts.len() > 1 {
+        info!("Found {} PlotNFTs", plotnfts.len());
+        let selected = prompts.select_plotnfts(&plotnfts)?;
+        plotnfts.retain(|p| selected.contains(&p.launcher_id));
+        if plotnfts.is_empty() {
+            warn!("No PlotNFT Selected");
+        }
+    }
+    if plotnfts.is_empty() {
+        upsert_farming_info(config, farming_info_for_key(master_key, None, None)?);
+    }
+    for plot_nft in plotnfts {
+        upsert_pool_info(config, &plot_nft, previous_payout_address)?;
+        let owner_keys = find_owner_keys(
+            master_key,
+            &plot_nft.pool_state.owner_pubkey,
+            scan.owner_key_indexes.clone(),
+        )?;
+        if owner_keys.is_none() {
+            let message = format!(
+                "No owner key in {:?} matches PlotNFT {}, widen the search with --owner-key-start/--owner-key-end",
+                scan.owner_key_indexes, plot_nft.launcher_id
+            );
+            //A PlotNFT that was asked for explicitly can not be farmed without pool authentication
+            if launcher_id.is_some() {
+                return Err(Error::new(ErrorKind::NotFound, message));
+            }
+            warn!("{message}");
+        }
+        upsert_farming_info(
+            config,
+            farming_info_for_key(master_key, Some(plot_nft.launcher_id), owner_keys)?,
+        );
+    }
+    Ok(())
+}
+
+//Adds or refreshes the pool_info of a PlotNFT from the chain
+pub fn upsert_pool_info(
+    config: &mut Config,
+    plot_nft: &PlotNft,
+    previous_payout_address: &str,
+) -> Result<(), Error> {
+    let mut pool_wallet = pool_wallet_config(plot_nft, &config.payout_address)?;
+    if let Some(pool) = config
+        .pool_info
+        .iter_mut()
+        .find(|p| p.launcher_id == plot_nft.launcher_id)
+    {
+        //Keep custom payout instructions and difficulty, refresh everything from the chain
+        if pool.payout_instructions != previous_payout_address {
+            pool_wallet.payout_instructions = pool.payout_instructions.clone();
+        }
+        pool_wallet.difficulty = pool.difficulty;
+        *pool = pool_wallet;
+    } else {
+        config.pool_info.push(pool_wallet);
+    }
+    Ok(())
+}
+
+//Replaces the entry for the same PlotNFT, or the same key when not pooling, otherwise appends.
+//A key that now farms a PlotNFT is no longer farmed solo, so its entry without a launcher is dropped
+pub fn upsert_farming_info(config: &mut Config, farming_info: FarmingInfo) {
+    if farming_info.launcher_id.is_some() {
+        config.farmer_info.retain(|f| {
+            f.launcher_id.is_some() || f.farmer_secret_key != farming_info.farmer_secret_key
+        });
+    }
+    if let Some(info) = config.farmer_info.iter_mut().find(|f| {
+        if farming_info.launcher_id.is_some() {
+            f.launcher_id == farming_info.launcher_id
+        } else {
+            f.launcher_id.is_none() && f.farmer_secret_key == farming_info.farmer_secret_key
+        }
+    }) {
+        *info = farming_info;
+    } else {
+        config.farmer_info.push(farming_info);
+    }
+}
+
+pub fn check_non_interactive_inputs(
+    gen_settings: &GenerateConfig,
+    existing: Option<&Config>,
+) -> Result<(), MissingInputs> {
+    let mut fields = vec![];
+    if gen_settings.mnemonics.is_empty() && gen_settings.keys.is_none() {
+        fields.push("--mnemonic-file, --mnemonic-stdin or --mnemonic-env");
+    }
+    if gen_settings.passphrase == Some(SecretSource::Prompt) {
+        fields.push("--passphrase-file or --passphrase-env");
+    }
+    if gen_settings.encrypt == Some(SecretSource::Prompt) {
+        fields.push("--config-passphrase-file or --config-passphrase-env");
+    }
+    //Everything else is taken from the config being merged into
+    if existing.is_some() {
+        return if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingInputs { fields })
+        };
+    }
+    if gen_settings.overwrite.is_none()
+        && gen_settings
+            .output_path
+            .as_ref()
+            .map(|p| p.exists())
+            .unwrap_or_default()
+    {
+        fields.push("--force or --no-overwrite");
+    }
+    if gen_settings.payout_address.is_none() {
+        fields.push("--payout-address");
+    }
+    if let Some(ws_host) = gen_settings
+        .fullnode_ws_host
+        .as_deref()
+        .map(resolve_fullnode_alias)
+    {
+        let rpc_host = if let Some(host) = &gen_settings.fullnode_rpc_host {
+            Some(resolve_fullnode_alias(host))
+        } else if "chia-proxy.evergreenminer-prod.com" == ws_host {
+            Some(ws_host.clone())
+        } else {
+            fields.push("--fullnode-rpc-host");
+            None
+        };
+        if gen_settings.fullnode_ws_port.is_none()
+            && !["chia-proxy.evergreenminer-prod.com", "localhost"].contains(&ws_host.as_str())
+        {
+            fields.push("--fullnode-ws-port");
+        }
+        if gen_settings.fullnode_rpc_port.is_none()
+            && !rpc_host
+                .map(|h| ["chia-proxy.evergreenminer-prod.com", "localhost"].contains(&h.as_str()))
+                .unwrap_or_default()
+        {
+            fields.push("--fullnode-rpc-port");
+        }
+    } else {
+        fields.push("--fullnode-ws-host");
+    }
+    if gen_settings.plot_directories.is_none() {
+        fields.push("--plot-directory");
+    }
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(MissingInputs { fields })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::{MockPrompts, PromptAnswers};
+    use crate::test_util::{farming_info, PAYOUT};
+
+    #[test]
+    fn prompts_for_a_local_node() {
+        let prompts = MockPrompts::new(PromptAnswers {
+            payout_address: Some(PAYOUT.to_string()),
+            fullnode_ws_host: Some("l".to_string()),
+            fullnode_rpc_host: Some("localhost".to_string()),
+            plot_directories: Some(vec!["/mnt/plots".to_string()]),
+            ..Default::default()
+        });
+        let config = prompt_for_config(&GenerateConfig::default(), None, &prompts).unwrap();
+        assert_eq!(config.payout_address, PAYOUT);
+        assert_eq!(config.fullnode_ws_host, "localhost");
+        assert_eq!(config.fullnode_ws_port, 8444);
+        assert_eq!(config.fullnode_rpc_host, "localhost");
+        assert_eq!(config.fullnode_rpc_port, 8555);
+        assert_eq!(config.ssl_root_path, None);
+        assert_eq!(
+            config.harvester_configs.gigahorse.unwrap().plot_directories,
+            vec!["/mnt/plots".to_string()]
+        );
+        //The ports of localhost are known, so they are not asked for
+        assert_eq!(
+            prompts.asked(),
+            vec![
+                "payout_address",
+                "fullnode_ws_host",
+                "fullnode_rpc_host",
+                "ssl_path",
+                "plot_directories"
+            ]
+        );
+    }
+
+    #[test]
+    fn community_node_skips_rpc_and_ssl_prompts() {
+        let prompts = MockPrompts::new(PromptAnswers {
+            payout_address: Some(PAYOUT.to_string()),
+            fullnode_ws_host: Some("community".to_string()),
+            ..Default::default()
+        });
+        let gen_settings = GenerateConfig {
+            plot_directories: Some(vec![]),
+            ..Default::default()
+        };
+        let config = prompt_for_config(&gen_settings, None, &prompts).unwrap();
+        assert_eq!(
+            config.fullnode_ws_host,
+            "chia-proxy.evergreenminer-prod.com"
+        );
+        assert_eq!(
+            config.fullnode_rpc_host,
+            "chia-proxy.evergreenminer-prod.com"
+        );
+        assert_eq!(config.fullnode_ws_port, 443);
+        assert_eq!(config.fullnode_rpc_port, 443);
+        assert_eq!(config.ssl_root_path, None);
+        assert_eq!(prompts.asked(), vec!["payout_address", "fullnode_ws_host"]);
+    }
+
+    #[test]
+    fn given_settings_are_not_prompted_for() {
+        let prompts = MockPrompts::new(PromptAnswers::default());
+        let gen_settings = GenerateConfig {
+            payout_address: Some(PAYOUT.to_string()),
+            fullnode_ws_host: Some("node.example.com".to_string()),
+            fullnode_ws_port: Some(18444),
+            fullnode_rpc_host: Some("node.example.com".to_string()),
+            fullnode_rpc_port: Some(18555),
+            network: Some("testnet11".to_string()),
+            plot_directories: Some(vec![]),
+            ..Default::default()
+        };
+        let config = prompt_for_config(&gen_settings, None, &prompts).unwrap();
+        assert_eq!(config.selected_network, "testnet11");
+        assert_eq!(config.fullnode_ws_port, 18444);
+        assert_eq!(config.fullnode_rpc_port, 18555);
+        //The payout address and host are still confirmed, prefilled with the given value
+        assert_eq!(
+            prompts.asked(),
+            vec!["payout_address", "fullnode_ws_host", "ssl_path"]
+        );
+    }
+
+    #[test]
+    fn merge_keeps_existing_values_of_a_community_config() {
+        let existing = Config {
+            fullnode_ws_host: "chia-proxy.evergreenminer-prod.com".to_string(),
+            fullnode_ws_port: 443,
+            fullnode_rpc_host: "rpc.example.com".to_string(),
+            fullnode_rpc_port: 18555,
+            ssl_root_path: None,
+            payout_address: PAYOUT.to_string(),
+            ..Default::default()
+        };
+        let prompts = MockPrompts::new(PromptAnswers::default());
+        let gen_settings = GenerateConfig {
+            merge: true,
+            ..Default::default()
+        };
+        let config = prompt_for_config(&gen_settings, Some(&existing), &prompts).unwrap();
+        assert_eq!(config, existing);
+        assert_eq!(
+            prompts.asked(),
+            vec![
+                "payout_address",
+                "fullnode_ws_host",
+                "fullnode_rpc_host",
+                "fullnode_ws_port",
+                "fullnode_rpc_port",
+                "ssl_path",
+                "plot_directories"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_prompt_answer_fails() {
+        let prompts = MockPrompts::new(PromptAnswers::default());
+        let error = prompt_for_config(&GenerateConfig::default(), None, &prompts).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(prompts.asked(), vec!["payout_address"]);
+    }
+
+    #[test]
+    fn non_interactive_lists_every_missing_input() {
+        let missing = check_non_interactive_inputs(&GenerateConfig::default(), None).unwrap_err();
+        assert_eq!(
+            missing.fields,
+            vec![
+                "--mnemonic-file, --mnemonic-stdin or --mnemonic-env",
+                "--payout-address",
+                "--fullnode-ws-host",
+                "--plot-directory"
+            ]
+        );
+        let gen_settings = GenerateConfig {
+            fullnode_ws_host: Some("node.example.com".to_string()),
+            passphrase: Some(SecretSource::Prompt),
+            ..Default::default()
+        };
+        let missing = check_non_interactive_inputs(&gen_settings, None).unwrap_err();
+        assert!(missing
+            .fields
+            .contains(&"--passphrase-file or --passphrase-env"));
+        assert!(missing.fields.contains(&"--fullnode-rpc-host"));
+        assert!(missing.fields.contains(&"--fullnode-ws-port"));
+        assert!(missing.fields.contains(&"--fullnode-rpc-port"));
+    }
+
+    #[test]
+    fn non_interactive_accepts_complete_inputs() {
+        let gen_settings = GenerateConfig {
+            mnemonics: vec![SecretSource::Stdin],
+            payout_address: Some(PAYOUT.to_string()),
+            fullnode_ws_host: Some("community".to_string()),
+            plot_directories: Some(vec![]),
+            ..Default::default()
+        };
+        assert!(check_non_interactive_inputs(&gen_settings, None).is_ok());
+        //Every prompt of a complete non-interactive run is answered from the given value
+        let prompts = MockPrompts::new(PromptAnswers::default());
+        prompt_for_config(&gen_settings, None, &prompts).unwrap();
+        assert_eq!(prompts.asked(), vec!["payout_address", "fullnode_ws_host"]);
+        //When merging everything but the mnemonic comes from the existing config
+        let merge_settings = GenerateConfig {
+            mnemonics: vec![SecretSource::Stdin],
+            ..Default::default()
+        };
+        assert!(check_non_interactive_inputs(&merge_settings, Some(&Config::default())).is_ok());
+    }
+
+    #[test]
+    fn upsert_replaces_the_solo_entry_of_a_key_that_joined_a_pool() {
+        let mut config = Config {
+            farmer_info: vec![farming_info(1, None), farming_info(2, None)],
+            ..Default::default()
+        };
+        upsert_farming_info(&mut config, farming_info(1, Some(10)));
+        upsert_farming_info(&mut config, farming_info(1, Some(11)));
+        assert_eq!(
+            config.farmer_info,
+            vec![
+                farming_info(2, None),
+                farming_info(1, Some(10)),
+                farming_info(1, Some(11))
+            ]
+        );
+        //Re-adding a solo key replaces its entry without touching the pooled ones
+        upsert_farming_info(&mut config, farming_info(2, None));
+        assert_eq!(config.farmer_info.len(), 3);
+    }
+}
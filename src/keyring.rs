@@ -0,0 +1,718 @@
+use crate::backup::save_config;
+use crate::config::{write_private_file, Config, EncryptedSecrets, FarmingInfo, SecretBytes32};
+use crate::encryption::{decrypt_if_encrypted, open, seal};
+use crate::errors::MissingInputs;
+use crate::generate::{add_key_to_config, upsert_farming_info, upsert_pool_info};
+use crate::keys::{owner_key_pair, public_key_for, secret_key_fingerprint};
+use crate::plotnft::find_plotnft_by_launcher_id;
+use crate::plotnft::ScanOptions;
+use crate::prompts::PromptProvider;
+use crate::rpc::rpc_client_from_config;
+use crate::secrets::{derive_master_key, read_bundle_passphrase, read_mnemonic, SecretSource};
+use blst::min_pk::SecretKey;
+use dg_xch_clients::rpc::full_node::FullnodeClient;
+use dg_xch_core::blockchain::sized_bytes::{prep_hex_str, Bytes32, Bytes48};
+use dg_xch_keys::{master_sk_to_farmer_sk, master_sk_to_pool_sk};
+use hex::decode;
+use log::info;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+pub struct AddKeyConfig {
+    pub config_path: PathBuf,
+    pub backup_retention: usize,
+    //Every source adds another key, the mnemonic is prompted for when empty
+    pub mnemonics: Vec<SecretSource>,
+    pub passphrase: Option<SecretSource>,
+    pub launcher_id: Option<Bytes32>,
+    pub scan: ScanOptions,
+    pub additional_headers: Option<HashMap<String, String>>,
+    pub non_interactive: bool,
+}
+
+//Lists the options key add needs in non-interactive mode, before the config is loaded or the network used
+pub fn check_add_key_inputs(add_settings: &AddKeyConfig) -> Result<(), MissingInputs> {
+    let mut fields = vec![];
+    if add_settings.mnemonics.is_empty() {
+        fields.push("--mnemonic-file, --mnemonic-stdin or --mnemonic-env");
+    }
+    if add_settings.passphrase == Some(SecretSource::Prompt) {
+        fields.push("--passphrase-file or --passphrase-env");
+    }
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(MissingInputs { fields })
+    }
+}
+
+//Identifies the entries removed by `key remove`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySelector {
+    FarmerPublicKey(Bytes48),
+    LauncherId(Bytes32),
+}
+impl TryFrom<&str> for KeySelector {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let bytes = decode(prep_hex_str(value.trim())).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("{value} is not valid hex: {e:?}"),
+            )
+        })?;
+        match bytes.len() {
+            48 => Ok(KeySelector::FarmerPublicKey(Bytes48::from(bytes))),
+            32 => Ok(KeySelector::LauncherId(Bytes32::from(bytes))),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{value} is neither a farmer public key (48 bytes) nor a launcher id (32 bytes)"),
+            )),
+        }
+    }
+}
+
+//Secret keys derived offline from a mnemonic, enough to farm without the mnemonic on the host
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExportedKeys {
+    pub farmer_secret_key: SecretBytes32,
+    pub pool_secret_key: Option<SecretBytes32>,
+    //The owner key of a PlotNFT is only known once it is looked up on chain,
+    //so a range of owner and auth keys is exported and matched on import
+    #[serde(default)]
+    pub owner_keys: Vec<OwnerKeyPair>,
+}
+impl ExportedKeys {
+    pub fn from_master_key(
+        master_key: &SecretKey,
+        owner_key_indexes: Range<u32>,
+    ) -> Result<Self, Error> {
+        let mut owner_keys = vec![];
+        for index in owner_key_indexes {
+            let (owner_secret_key, auth_secret_key) = owner_key_pair(master_key, index)?;
+            owner_keys.push(OwnerKeyPair {
+                index: Some(index),
+                owner_secret_key,
+                auth_secret_key,
+            });
+        }
+        Ok(Self {
+            farmer_secret_key: master_sk_to_farmer_sk(master_key)?.into(),
+            pool_secret_key: Some(master_sk_to_pool_sk(master_key)?.into()),
+            owner_keys,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OwnerKeyPair {
+    //Derivation index, not known for keys exported from a config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    pub owner_secret_key: SecretBytes32,
+    pub auth_secret_key: SecretBytes32,
+}
+
+//Written in the clear and authenticated with the ciphertext, so a bundle can not be
+//passed off as encrypted_farmer_info or a future bundle version
+pub const KEY_BUNDLE_FORMAT: &str = "fast_farmer_config/key-bundle/v1";
+
+//The secrets needed to farm and the PlotNFTs they farm, nothing that could derive other keys
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct KeyBundle {
+    pub keys: Vec<ExportedKeys>,
+    #[serde(default)]
+    pub launcher_ids: Vec<Bytes32>,
+}
+impl KeyBundle {
+    //Groups farmer_info by farmer key, keeping the owner keys already matched to PlotNFTs
+    pub fn from_farmer_info(farmer_info: &[FarmingInfo]) -> Self {
+        let mut bundle = KeyBundle::default();
+        for info in farmer_info {
+            let position = match bundle
+                .keys
+                .iter()
+                .position(|k| k.farmer_secret_key == info.farmer_secret_key)
+            {
+                Some(position) => position,
+                None => {
+                    bundle.keys.push(ExportedKeys {
+                        farmer_secret_key: info.farmer_secret_key.clone(),
+                        pool_secret_key: None,
+                        owner_keys: vec![],
+                    });
+                    bundle.keys.len() - 1
+                }
+            };
+            let keys = &mut bundle.keys[position];
+            if keys.pool_secret_key.is_none() {
+                keys.pool_secret_key = info.pool_secret_key.clone();
+            }
+            if let (Some(owner_secret_key), Some(auth_secret_key)) =
+                (&info.owner_secret_key, &info.auth_secret_key)
+            {
+                if !keys
+                    .owner_keys
+                    .iter()
+                    .any(|k| k.owner_secret_key == *owner_secret_key)
+                {
+                    keys.owner_keys.push(OwnerKeyPair {
+                        index: None,
+                        owner_secret_key: owner_secret_key.clone(),
+                        auth_secret_key: auth_secret_key.clone(),
+                    });
+                }
+            }
+            if let Some(launcher_id) = info.launcher_id {
+                if !bundle.launcher_ids.contains(&launcher_id) {
+                    bundle.launcher_ids.push(launcher_id);
+                }
+            }
+        }
+        bundle
+    }
+    pub fn save<P: AsRef<Path>>(&self, path: P, passphrase: &str) -> Result<(), Error> {
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&self).map_err(|e| Error::other(format!("{:?}", e)))?,
+        );
+        let file = KeyBundleFile {
+            format: KEY_BUNDLE_FORMAT.to_string(),
+            encrypted: seal(passphrase, &plaintext, KEY_BUNDLE_FORMAT.as_bytes())?,
+        };
+        write_private_file(
+            path.as_ref(),
+            serde_yaml::to_string(&file)
+                .map_err(|e| Error::other(format!("{:?}", e)))?
+                .as_bytes(),
+        )
+    }
+    pub fn load<P: AsRef<Path>>(path: P, passphrase: &str) -> Result<Self, Error> {
+        let file: KeyBundleFile = serde_yaml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("Invalid key bundle: {e}")))?;
+        if file.format != KEY_BUNDLE_FORMAT {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                format!("Unsupported key bundle format {}", file.format),
+            ));
+        }
+        let plaintext = open(
+            &file.encrypted,
+            passphrase,
+            KEY_BUNDLE_FORMAT.as_bytes(),
+            "key bundle",
+        )?;
+        serde_json::from_slice(&plaintext).map_err(|e| Error::other(format!("{:?}", e)))
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct KeyBundleFile {
+    format: String,
+    #[serde(flatten)]
+    encrypted: EncryptedSecrets,
+}
+
+pub struct ExportKeyConfig {
+    pub output_path: PathBuf,
+    //Exports the farmer_info of this config instead of deriving keys from mnemonics
+    pub config_path: Option<PathBuf>,
+    pub config_passphrase: Option<SecretSource>,
+    //Every source adds another key, the mnemonic is prompted for when empty
+    pub mnemonics: Vec<SecretSource>,
+    pub passphrase: Option<SecretSource>,
+    pub owner_key_indexes: Range<u32>,
+    pub launcher_ids: Vec<Bytes32>,
+    pub bundle_passphrase: Option<SecretSource>,
+}
+
+pub struct ImportKeyConfig {
+    pub config_path: PathBuf,
+    pub backup_retention: usize,
+    pub bundle_path: PathBuf,
+    pub bundle_passphrase: Option<SecretSource>,
+    //Farmed in addition to the launcher ids in the bundle
+    pub launcher_ids: Vec<Bytes32>,
+    pub additional_headers: Option<HashMap<String, String>>,
+}
+
+pub struct KeySummary {
+    pub fingerprint: u32,
+    pub farmer_public_key: Bytes48,
+    pub launcher_id: Option<Bytes32>,
+    pub pool_url: Option<String>,
+}
+
+pub async fn add_keys(
+    add_settings: AddKeyConfig,
+    prompts: &dyn PromptProvider,
+) -> Result<Config, Error> {
+    if add_settings.non_interactive {
+        check_add_key_inputs(&add_settings)?;
+    }
+    let mut config = Config::try_from(&add_settings.config_path)?;
+    config.require_decrypted()?;
+    let mut master_keys = vec![];
+    for source in &add_settings.mnemonics {
+        master_keys.push(derive_master_key(
+            &read_mnemonic(source, prompts)?,
+            add_settings.passphrase.as_ref(),
+            prompts,
+        )?);
+    }
+    if master_keys.is_empty() {
+        master_keys.push(derive_master_key(
+            &prompts.mnemonic()?,
+            add_settings.passphrase.as_ref(),
+            prompts,
+        )?);
+    }
+    let client = rpc_client_from_config(&config, &add_settings.additional_headers);
+    let payout_address = config.payout_address.clone();
+    let mut launcher_id = add_settings.launcher_id;
+    for master_key in &master_keys {
+        add_key_to_config(
+            &mut config,
+            master_key,
+            client.clone(),
+            launcher_id.take(),
+            &add_settings.scan,
+            &payout_address,
+            prompts,
+        )
+        .await?;
+    }
+    save_config(
+        &config,
+        &add_settings.config_path,
+        add_settings.backup_retention,
+    )?;
+    info!("Saved Config to {:?}", add_settings.config_path);
+    Ok(config)
+}
+
+//Builds and saves a key bundle without touching the network, so it can run on an offline machine
+pub fn export_keys(
+    export_settings: ExportKeyConfig,
+    prompts: &dyn PromptProvider,
+) -> Result<KeyBundle, Error> {
+    let mut bundle = if let Some(config_path) = &export_settings.config_path {
+        let mut config = Config::try_from(config_path)?;
+        decrypt_if_encrypted(
+            &mut config,
+            export_settings.config_passphrase.as_ref(),
+            prompts,
+        )?;
+        KeyBundle::from_farmer_info(&config.farmer_info)
+    } else {
+        let mut sources = export_settings.mnemonics.clone();
+        if sources.is_empty() {
+            sources.push(SecretSource::Prompt);
+        }
+        let mut bundle = KeyBundle::default();
+        for source in &sources {
+            let master_key = derive_master_key(
+                &read_mnemonic(source, prompts)?,
+                export_settings.passphrase.as_ref(),
+                prompts,
+            )?;
+            bundle.keys.push(ExportedKeys::from_master_key(
+                &master_key,
+                export_settings.owner_key_indexes.clone(),
+            )?);
+        }
+        bundle
+    };
+    if bundle.keys.is_empty() {
+        return Err(Error::new(ErrorKind::NotFound, "No keys to export"));
+    }
+    for launcher_id in &export_settings.launcher_ids {
+        if !bundle.launcher_ids.contains(launcher_id) {
+            bundle.launcher_ids.push(*launcher_id);
+        }
+    }
+    let passphrase =
+        read_bundle_passphrase(export_settings.bundle_passphrase.as_ref(), true, prompts)?;
+    bundle.save(&export_settings.output_path, &passphrase)?;
+    info!(
+        "Exported {} key(s) and {} PlotNFT(s) to {:?}",
+        bundle.keys.len(),
+        bundle.launcher_ids.len(),
+        export_settings.output_path
+    );
+    Ok(bundle)
+}
+
+pub async fn import_key_file(
+    import_settings: ImportKeyConfig,
+    prompts: &dyn PromptProvider,
+) -> Result<Config, Error> {
+    let mut config = Config::try_from(&import_settings.config_path)?;
+    config.require_decrypted()?;
+    let passphrase =
+        read_bundle_passphrase(import_settings.bundle_passphrase.as_ref(), false, prompts)?;
+    let mut bundle = KeyBundle::load(&import_settings.bundle_path, &passphrase)?;
+    for launcher_id in &import_settings.launcher_ids {
+        if !bundle.launcher_ids.contains(launcher_id) {
+            bundle.launcher_ids.push(*launcher_id);
+        }
+    }
+    let client = rpc_client_from_config(&config, &import_settings.additional_headers);
+    let payout_address = config.payout_address.clone();
+    import_keys(
+        &mut config,
+        &bundle.keys,
+        &bundle.launcher_ids,
+        client,
+        &payout_address,
+    )
+    .await?;
+    save_config(
+        &config,
+        &import_settings.config_path,
+        import_settings.backup_retention,
+    )?;
+    info!("Saved Config to {:?}", import_settings.config_path);
+    Ok(config)
+}
+
+//Adds farmer_info for supplied keys. Each PlotNFT is looked up on chain and assigned to the key
+//holding its owner key, keys without a PlotNFT are added for solo farming
+pub async fn import_keys(
+    config: &mut Config,
+    keys: &[ExportedKeys],
+    launcher_ids: &[Bytes32],
+    client: Arc<FullnodeClient>,
+    previous_payout_address: &str,
+) -> Result<(), Error> {
+    for key in keys {
+        info!(
+            "Importing key with fingerprint {}",
+            secret_key_fingerprint(&key.farmer_secret_key)
+        );
+    }
+    let mut pooling = vec![false; keys.len()];
+    for launcher_id in launcher_ids {
+        let plot_nft = find_plotnft_by_launcher_id(client.clone(), launcher_id).await?;
+        let found = keys.iter().enumerate().find_map(|(position, key)| {
+            key.owner_keys
+                .iter()
+                .find(|k| public_key_for(&k.owner_secret_key) == plot_nft.pool_state.owner_pubkey)
+                .map(|owner_keys| (position, owner_keys))
+        });
+        //Every launcher id was asked for explicitly, farming it without pool authentication would fail later
+        let Some((position, owner_keys)) = found else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "No owner key matches PlotNFT {launcher_id}, export the keys with a wider --owner-key-start/--owner-key-end range"
+                ),
+            ));
+        };
+        pooling[position] = true;
+        upsert_pool_info(config, &plot_nft, previous_payout_address)?;
+        upsert_farming_info(
+            config,
+            FarmingInfo {
+                farmer_secret_key: keys[position].farmer_secret_key.clone(),
+                launcher_id: Some(*launcher_id),
+                pool_secret_key: keys[position].pool_secret_key.clone(),
+                owner_secret_key: Some(owner_keys.owner_secret_key.clone()),
+                auth_secret_key: Some(owner_keys.auth_secret_key.clone()),
+            },
+        );
+    }
+    for (key, pooling) in keys.iter().zip(pooling) {
+        if !pooling {
+            upsert_farming_info(
+                config,
+                FarmingInfo {
+                    farmer_secret_key: key.farmer_secret_key.clone(),
+                    launcher_id: None,
+                    pool_secret_key: key.pool_secret_key.clone(),
+                    owner_secret_key: None,
+                    auth_secret_key: None,
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+//Removes the farmer_info entries matching the selector along with the pool_info entries of their
+//PlotNFTs that no remaining farmer_info entry points at. Other pool_info entries are kept, unless
+//selected by launcher id, which also removes a pool_info entry without any farmer_info
+pub fn remove_keys(config: &mut Config, selector: KeySelector) -> Result<(), Error> {
+    config.require_decrypted()?;
+    let matches = |f: &FarmingInfo| match selector {
+        KeySelector::FarmerPublicKey(public_key) => {
+            public_key_for(&f.farmer_secret_key) == public_key
+        }
+        KeySelector::LauncherId(launcher_id) => f.launcher_id == Some(launcher_id),
+    };
+    let mut removed_launcher_ids: Vec<Bytes32> = config
+        .farmer_info
+        .iter()
+        .filter(|f| matches(f))
+        .filter_map(|f| f.launcher_id)
+        .collect();
+    if let KeySelector::LauncherId(launcher_id) = selector {
+        removed_launcher_ids.push(launcher_id);
+    }
+    let before = (config.farmer_info.len(), config.pool_info.len());
+    config.farmer_info.retain(|f| !matches(f));
+    let farmer_info = &config.farmer_info;
+    config.pool_info.retain(|p| {
+        !removed_launcher_ids.contains(&p.launcher_id)
+            || farmer_info
+                .iter()
+                .any(|f| f.launcher_id == Some(p.launcher_id))
+    });
+    if (config.farmer_info.len(), config.pool_info.len()) == before {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("No farmer_info or pool_info entries match {selector:?}"),
+        ));
+    }
+    Ok(())
+}
+
+pub fn list_keys(config: &Config) -> Vec<KeySummary> {
+    config
+        .farmer_info
+        .iter()
+        .map(|f| KeySummary {
+            fingerprint: secret_key_fingerprint(&f.farmer_secret_key),
+            farmer_public_key: public_key_for(&f.farmer_secret_key),
+            launcher_id: f.launcher_id,
+            pool_url: f.launcher_id.and_then(|l| {
+                config
+                    .pool_info
+                    .iter()
+                    .find(|p| p.launcher_id == l)
+                    .map(|p| p.pool_url.clone())
+            }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::FARMER_INFO_AAD;
+    use crate::test_util::{bytes32, farming_info, pool, secret_key};
+
+    fn pool_launcher_ids(config: &Config) -> Vec<Bytes32> {
+        config.pool_info.iter().map(|p| p.launcher_id).collect()
+    }
+
+    #[tokio::test]
+    async fn non_interactive_add_lists_missing_inputs_before_loading_the_config() {
+        let add_settings = AddKeyConfig {
+            config_path: PathBuf::from("missing/fast_farmer.yaml"),
+            backup_retention: 0,
+            mnemonics: vec![],
+            passphrase: Some(SecretSource::Prompt),
+            launcher_id: None,
+            scan: ScanOptions::default(),
+            additional_headers: None,
+            non_interactive: true,
+        };
+        assert_eq!(
+            check_add_key_inputs(&add_settings).unwrap_err().fields,
+            vec![
+                "--mnemonic-file, --mnemonic-stdin or --mnemonic-env",
+                "--passphrase-file or --passphrase-env"
+            ]
+        );
+        let prompts = crate::prompts::non_interactive_prompts();
+        let error = add_keys(add_settings, &prompts).await.unwrap_err();
+        assert_eq!(
+            crate::errors::exit_code_for(&error),
+            crate::errors::EXIT_MISSING_INPUT
+        );
+        let add_settings = AddKeyConfig {
+            config_path: PathBuf::from("missing/fast_farmer.yaml"),
+            backup_retention: 0,
+            mnemonics: vec![SecretSource::Stdin],
+            passphrase: None,
+            launcher_id: None,
+            scan: ScanOptions::default(),
+            additional_headers: None,
+            non_interactive: true,
+        };
+        assert!(check_add_key_inputs(&add_settings).is_ok());
+    }
+
+    #[test]
+    fn remove_launcher_id_removes_an_orphan_pool_info() {
+        //Pool 9 was added by hand without farmer_info
+        let mut config = Config {
+            farmer_info: vec![farming_info(1, Some(10))],
+            pool_info: vec![pool(9), pool(10)],
+            ..Default::default()
+        };
+        remove_keys(&mut config, KeySelector::LauncherId(bytes32(9))).unwrap();
+        assert_eq!(pool_launcher_ids(&config), vec![bytes32(10)]);
+        assert_eq!(config.farmer_info, vec![farming_info(1, Some(10))]);
+        let error = remove_keys(&mut config, KeySelector::LauncherId(bytes32(9))).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn remove_key_keeps_unrelated_pool_info() {
+        //Pool 9 has no farmer_info entry, it was added by hand and must survive
+        let mut config = Config {
+            farmer_info: vec![
+                farming_info(1, Some(10)),
+                farming_info(1, Some(11)),
+                farming_info(2, Some(20)),
+            ],
+            pool_info: vec![pool(10), pool(11), pool(20), pool(9)],
+            ..Default::default()
+        };
+        let public_key = public_key_for(&secret_key(1));
+        remove_keys(&mut config, KeySelector::FarmerPublicKey(public_key)).unwrap();
+        assert_eq!(config.farmer_info, vec![farming_info(2, Some(20))]);
+        assert_eq!(pool_launcher_ids(&config), vec![bytes32(20), bytes32(9)]);
+    }
+
+    #[test]
+    fn remove_launcher_id_keeps_pool_info_still_farmed() {
+        let mut config = Config {
+            farmer_info: vec![farming_info(1, Some(10)), farming_info(2, Some(10))],
+            pool_info: vec![pool(10)],
+            ..Default::default()
+        };
+        remove_keys(
+            &mut config,
+            KeySelector::FarmerPublicKey(public_key_for(&secret_key(1))),
+        )
+        .unwrap();
+        assert_eq!(pool_launcher_ids(&config), vec![bytes32(10)]);
+        remove_keys(&mut config, KeySelector::LauncherId(bytes32(10))).unwrap();
+        assert!(config.farmer_info.is_empty());
+        assert!(config.pool_info.is_empty());
+    }
+
+    #[test]
+    fn remove_without_match_fails() {
+        let mut config = Config {
+            farmer_info: vec![farming_info(1, None)],
+            ..Default::default()
+        };
+        let error = remove_keys(&mut config, KeySelector::LauncherId(bytes32(10))).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(config.farmer_info.len(), 1);
+    }
+
+    fn bundle() -> KeyBundle {
+        KeyBundle {
+            keys: vec![ExportedKeys {
+                farmer_secret_key: secret_key(1),
+                pool_secret_key: Some(secret_key(2)),
+                owner_keys: vec![OwnerKeyPair {
+                    index: Some(0),
+                    owner_secret_key: secret_key(3),
+                    auth_secret_key: secret_key(4),
+                }],
+            }],
+            launcher_ids: vec![bytes32(10)],
+        }
+    }
+
+    fn load_error(path: &Path, passphrase: &str) -> ErrorKind {
+        KeyBundle::load(path, passphrase).unwrap_err().kind()
+    }
+
+    #[test]
+    fn bundle_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.bundle");
+        bundle().save(&path, "correct horse").unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(KEY_BUNDLE_FORMAT));
+        assert!(!contents.contains(&hex::encode([3u8; 32])));
+        assert_eq!(KeyBundle::load(&path, "correct horse").unwrap(), bundle());
+        assert_eq!(
+            load_error(&path, "wrong horse"),
+            ErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn bundle_rejects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.bundle");
+        bundle().save(&path, "correct horse").unwrap();
+        let mut file: KeyBundleFile =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let mut ciphertext = decode(&file.encrypted.ciphertext).unwrap();
+        let last = ciphertext.len() - 1;
+        ciphertext[last] ^= 1;
+        file.encrypted.ciphertext = hex::encode(ciphertext);
+        let tampered = dir.path().join("tampered.bundle");
+        fs::write(&tampered, serde_yaml::to_string(&file).unwrap()).unwrap();
+        assert_eq!(
+            load_error(&tampered, "correct horse"),
+            ErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn bundle_format_is_authenticated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.bundle");
+        //Encrypted farmer_info uses other associated data, so it can not be loaded as a bundle
+        let plaintext = serde_json::to_vec(&bundle()).unwrap();
+        let file = KeyBundleFile {
+            format: KEY_BUNDLE_FORMAT.to_string(),
+            encrypted: seal("correct horse", &plaintext, FARMER_INFO_AAD).unwrap(),
+        };
+        fs::write(&path, serde_yaml::to_string(&file).unwrap()).unwrap();
+        assert_eq!(
+            load_error(&path, "correct horse"),
+            ErrorKind::PermissionDenied
+        );
+        //Neither can a bundle be opened as another format
+        bundle().save(&path, "correct horse").unwrap();
+        let file: KeyBundleFile =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            open(&file.encrypted, "correct horse", FARMER_INFO_AAD, "test")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::PermissionDenied
+        );
+        let renamed = KeyBundleFile {
+            format: "fast_farmer_config/key-bundle/v2".to_string(),
+            ..file
+        };
+        fs::write(&path, serde_yaml::to_string(&renamed).unwrap()).unwrap();
+        assert_eq!(load_error(&path, "correct horse"), ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn bundle_from_farmer_info_groups_by_farmer_key() {
+        let mut pooling = farming_info(1, Some(10));
+        pooling.owner_secret_key = Some(secret_key(3));
+        pooling.auth_secret_key = Some(secret_key(4));
+        let bundle = KeyBundle::from_farmer_info(&[
+            pooling.clone(),
+            farming_info(1, None),
+            pooling,
+            farming_info(5, Some(11)),
+        ]);
+        assert_eq!(bundle.keys.len(), 2);
+        assert_eq!(bundle.keys[0].farmer_secret_key, secret_key(1));
+        assert_eq!(bundle.keys[0].owner_keys.len(), 1);
+        assert_eq!(bundle.keys[0].owner_keys[0].index, None);
+        assert_eq!(bundle.keys[0].owner_keys[0].auth_secret_key, secret_key(4));
+        assert!(bundle.keys[1].owner_keys.is_empty());
+        assert_eq!(bundle.launcher_ids, vec![bytes32(10), bytes32(11)]);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::config::{FarmingInfo, SecretBytes32};
+use bip39::Mnemonic;
+use blst::min_pk::SecretKey;
+use dg_xch_core::blockchain::sized_bytes::{prep_hex_str, Bytes32, Bytes48, SizedBytes};
+use dg_xch_keys::{
+    fingerprint, master_sk_to_farmer_sk, master_sk_to_pool_sk,
+    master_sk_to_pooling_authentication_sk, master_sk_to_singleton_owner_sk,
+    master_sk_to_wallet_sk, master_sk_to_wallet_sk_unhardened,
+};
+use dg_xch_puzzles::p2_delegated_puzzle_or_hidden_puzzle::puzzle_hash_for_pk;
+use hex::decode;
+use std::io::{Error, ErrorKind};
+use std::ops::Range;
+use zeroize::Zeroizing;
+
+//Returns the unhardened and hardened wallet puzzle hashes for every index in the range
+pub fn wallet_puzzle_hashes(
+    master_key: &SecretKey,
+    indexes: Range<u32>,
+) -> Result<Vec<Bytes32>, Error> {
+    let mut puzzle_hashes = vec![];
+    for index in indexes {
+        let wallet_sk = master_sk_to_wallet_sk_unhardened(master_key, index).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Failed to parse Wallet SK: {:?}", e),
+            )
+        })?;
+        let pub_key: Bytes48 = wallet_sk.sk_to_pk().to_bytes().into();
+        puzzle_hashes.push(puzzle_hash_for_pk(&pub_key)?);
+        let hardened_wallet_sk = master_sk_to_wallet_sk(master_key, index).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Failed to parse Wallet SK: {:?}", e),
+            )
+        })?;
+        let pub_key: Bytes48 = hardened_wallet_sk.sk_to_pk().to_bytes().into();
+        puzzle_hashes.push(puzzle_hash_for_pk(&pub_key)?);
+    }
+    Ok(puzzle_hashes)
+}
+
+//The singleton owner key and the pool authentication key for a PlotNFT created at index
+pub fn owner_key_pair(
+    master_key: &SecretKey,
+    index: u32,
+) -> Result<(SecretBytes32, SecretBytes32), Error> {
+    Ok((
+        master_sk_to_singleton_owner_sk(master_key, index)?.into(),
+        master_sk_to_pooling_authentication_sk(master_key, index, 0)?.into(),
+    ))
+}
+
+//Searches the singleton owner keys for one matching the PlotNFT owner, returns (owner, auth)
+pub fn find_owner_keys(
+    master_key: &SecretKey,
+    owner_public_key: &Bytes48,
+    indexes: Range<u32>,
+) -> Result<Option<(SecretBytes32, SecretBytes32)>, Error> {
+    for i in indexes {
+        let key = master_sk_to_singleton_owner_sk(master_key, i)?;
+        let pub_key: Bytes48 = key.sk_to_pk().to_bytes().into();
+        if &pub_key == owner_public_key {
+            return owner_key_pair(master_key, i).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+pub fn farming_info_for_key(
+    master_key: &SecretKey,
+    launcher_id: Option<Bytes32>,
+    owner_keys: Option<(SecretBytes32, SecretBytes32)>,
+) -> Result<FarmingInfo, Error> {
+    let (owner_secret_key, auth_secret_key) = match owner_keys {
+        Some((owner, auth)) => (Some(owner), Some(auth)),
+        None => (None, None),
+    };
+    //The derived blst keys wipe themselves when dropped right after being wrapped here
+    Ok(FarmingInfo {
+        farmer_secret_key: master_sk_to_farmer_sk(master_key)?.into(),
+        launcher_id,
+        pool_secret_key: Some(master_sk_to_pool_sk(master_key)?.into()),
+        owner_secret_key,
+        auth_secret_key,
+    })
+}
+
+//Fingerprint of the public key belonging to a secret key, safe to print in place of the secret
+pub fn secret_key_fingerprint(secret_key: &SecretBytes32) -> u32 {
+    fingerprint(&SecretKey::from(secret_key.expose()).sk_to_pk())
+}
+
+//The value printed in place of a secret key in redacted output
+pub fn redact_secret_key(secret_key: &SecretBytes32) -> String {
+    format!("fingerprint {}", secret_key_fingerprint(secret_key))
+}
+
+pub fn public_key_for(secret_key: &SecretBytes32) -> Bytes48 {
+    SecretKey::from(secret_key.expose())
+        .sk_to_pk()
+        .to_bytes()
+        .into()
+}
+
+//Same derivation as key_from_mnemonic with the optional BIP39 passphrase (25th word) mixed into the seed
+pub fn master_key_from_mnemonic(mnemonic: &Mnemonic, passphrase: &str) -> Result<SecretKey, Error> {
+    let seed = Zeroizing::new(mnemonic.to_seed(passphrase));
+    SecretKey::key_gen_v3(seed.as_slice(), &[])
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("{:?}", e)))
+}
+
+//Parses a hex secret key as supplied on the command line
+pub fn parse_secret_key(input: &str) -> Result<SecretBytes32, Error> {
+    let bytes = Zeroizing::new(decode(prep_hex_str(input.trim())).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Secret key is not valid hex: {e:?}"),
+        )
+    })?);
+    if bytes.len() != 32 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Secret key must be 32 bytes, got {}", bytes.len()),
+        ));
+    }
+    //SecretKey::from(&Bytes32) falls back to a default key for invalid scalars, so check it here
+    SecretKey::from_bytes(&bytes).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Secret key is not a valid BLS secret key: {e:?}"),
+        )
+    })?;
+    Ok(SecretBytes32::from(Bytes32::new(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_secret_keys() {
+        let secret_key = parse_secret_key(&format!("0x{}", "01".repeat(32))).unwrap();
+        assert_eq!(secret_key.expose(), &Bytes32::new(&[1u8; 32]));
+    }
+
+    #[test]
+    fn rejects_invalid_secret_keys() {
+        for input in ["zz", "0x0101", &"ff".repeat(32), &"00".repeat(32)] {
+            assert_eq!(
+                parse_secret_key(input).unwrap_err().kind(),
+                ErrorKind::InvalidInput,
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::config::Config;
+use home::home_dir;
+use std::path::{Path, PathBuf};
+
+pub mod backup;
+pub mod check;
+pub mod chia;
+pub mod config;
+pub mod diff;
+pub mod edit;
+pub mod encryption;
+pub mod errors;
+pub mod generate;
+pub mod keyring;
+pub mod keys;
+pub mod merge;
+pub mod plotnft;
+pub mod prompts;
+pub mod rpc;
+pub mod secrets;
+pub mod ssl;
+pub mod validate;
+
+#[cfg(test)]
+mod test_util;
+
+pub static PRIVATE_CRT: &str = "farmer/private_farmer.crt";
+pub static PRIVATE_KEY: &str = "farmer/private_farmer.key";
+pub static CA_PRIVATE_CRT: &str = "ca/private_ca.crt";
+pub static CA_PRIVATE_KEY: &str = "ca/private_ca.key";
+
+pub fn get_root_path() -> PathBuf {
+    let prefix = home_dir().unwrap_or(Path::new("/").to_path_buf());
+    prefix.as_path().join(Path::new(".config/fast_farmer/"))
+}
+
+pub fn get_ssl_root_path(config: &Config) -> PathBuf {
+    if let Some(ssl_root_path) = &config.ssl_root_path {
+        PathBuf::from(ssl_root_path)
+    } else {
+        get_root_path().as_path().join(Path::new("ssl/"))
+    }
+}
+
+pub fn get_config_path() -> PathBuf {
+    get_root_path()
+        .as_path()
+        .join(Path::new("fast_farmer.yaml"))
+}
@@ -1,104 +1,704 @@
-use crate::config::{Config, FarmingInfo, GigahorseHarvesterConfig};
-use crate::prompts::{
-    prompt_for_farming_fullnode, prompt_for_farming_port, prompt_for_launcher_id,
-    prompt_for_mnemonic, prompt_for_payout_address, prompt_for_plot_directories,
-    prompt_for_rpc_fullnode, prompt_for_rpc_port, prompt_for_ssl_path,
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dg_xch_core::blockchain::sized_bytes::Bytes32;
+use fast_farmer_config::backup::{
+    list_backups, restore_backup, restore_changes, save_config, DEFAULT_BACKUP_RETENTION,
 };
-use clap::Parser;
-use dg_xch_cli::wallets::plotnft_utils::{get_plotnft_by_launcher_id, scrounge_for_plotnfts};
-use dg_xch_clients::rpc::full_node::FullnodeClient;
-use dg_xch_clients::ClientSSLConfig;
-use dg_xch_core::blockchain::sized_bytes::{Bytes32, Bytes48};
-use dg_xch_core::config::PoolWalletConfig;
-use dg_xch_core::consensus::constants::CONSENSUS_CONSTANTS_MAP;
-use dg_xch_core::ssl::create_all_ssl;
-use dg_xch_keys::{
-    key_from_mnemonic, master_sk_to_farmer_sk, master_sk_to_pool_sk,
-    master_sk_to_pooling_authentication_sk, master_sk_to_singleton_owner_sk,
-    master_sk_to_wallet_sk, master_sk_to_wallet_sk_unhardened,
+use fast_farmer_config::check::{check_connectivity, first_failure, DEFAULT_CHECK_TIMEOUT};
+use fast_farmer_config::chia::default_chia_root;
+use fast_farmer_config::config::Config;
+use fast_farmer_config::diff::diff_configs;
+use fast_farmer_config::edit::{edit_config, EditConfig};
+use fast_farmer_config::encryption::{
+    decrypt_farmer_info, decrypt_if_encrypted, encrypt_farmer_info,
 };
-use dg_xch_puzzles::clvm_puzzles::launcher_id_to_p2_puzzle_hash;
-use dg_xch_puzzles::p2_delegated_puzzle_or_hidden_puzzle::puzzle_hash_for_pk;
-use dialoguer::Confirm;
-use home::home_dir;
-use log::{info, warn, LevelFilter};
+use fast_farmer_config::errors::{exit_code_for, InvalidConfig, MissingInputs};
+use fast_farmer_config::generate::{generate_config_from_mnemonic, GenerateConfig};
+use fast_farmer_config::keyring::{
+    add_keys, export_keys, import_key_file, list_keys, remove_keys, AddKeyConfig, ExportKeyConfig,
+    ExportedKeys, ImportKeyConfig, KeySelector, OwnerKeyPair,
+};
+use fast_farmer_config::keys::parse_secret_key;
+use fast_farmer_config::plotnft::ScanOptions;
+use fast_farmer_config::prompts::{
+    non_interactive_prompts, DialoguerPrompts, PromptProvider, ScriptedPrompts,
+};
+use fast_farmer_config::rpc::check_rpc_connection;
+use fast_farmer_config::secrets::{read_config_passphrase, SecretSource};
+use fast_farmer_config::ssl::{check_ssl, regenerate_ssl, setup_ssl};
+use fast_farmer_config::validate::validate_config;
+use fast_farmer_config::{get_config_path, get_root_path, get_ssl_root_path};
+use log::{error, info, LevelFilter};
 use simple_logger::SimpleLogger;
-use std::collections::HashMap;
 use std::env;
 use std::io::{Error, ErrorKind};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::process::exit;
+use std::time::Duration;
 use tokio::fs::create_dir_all;
 
-mod config;
-mod prompts;
-
-pub static PRIVATE_CRT: &str = "farmer/private_farmer.crt";
-pub static PRIVATE_KEY: &str = "farmer/private_farmer.key";
-pub static CA_PRIVATE_CRT: &str = "ca/private_ca.crt";
-
 #[tokio::main]
-async fn main() -> Result<(), Error> {
+async fn main() {
     SimpleLogger::new()
         .with_colors(true)
         .with_level(LevelFilter::Info)
         .env()
         .init()
         .unwrap_or_default();
-    let cli = Cli::parse();
+    if let Err(e) = run(Cli::parse()).await {
+        error!("{e}");
+        exit(exit_code_for(&e));
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Error> {
     let config_path = if let Some(s) = &cli.config {
         PathBuf::from(s)
     } else if let Ok(s) = env::var("CONFIG_PATH") {
         PathBuf::from(s)
     } else {
-        let config_path = get_config_path();
-        if let Some(parent) = config_path.parent() {
-            create_dir_all(parent).await?;
-        }
-        config_path
+        get_config_path()
     };
-    generate_config_from_mnemonic(GenerateConfig {
-        output_path: Some(config_path),
-        mnemonic_file: cli.mnemonic_file,
-        fullnode_ws_host: cli.fullnode_ws_host,
-        fullnode_ws_port: cli.fullnode_ws_port,
-        fullnode_rpc_host: cli.fullnode_rpc_host,
-        fullnode_rpc_port: cli.fullnode_rpc_port,
-        fullnode_ssl: cli.fullnode_ssl,
-        network: cli.network,
-        launcher_id: cli.launcher_id.map(Bytes32::from),
-        payout_address: cli.payout_address,
-        plot_directories: cli.plot_directories,
-        additional_headers: None,
-    })
-    .await?;
-    Ok(())
-}
-
-pub(crate) fn get_root_path() -> PathBuf {
-    let prefix = home_dir().unwrap_or(Path::new("/").to_path_buf());
-    prefix.as_path().join(Path::new(".config/fast_farmer/"))
-}
-
-pub(crate) fn get_ssl_root_path(config: &Config) -> PathBuf {
-    if let Some(ssl_root_path) = &config.ssl_root_path {
-        PathBuf::from(ssl_root_path)
+    let prompts: Box<dyn PromptProvider> = if cli.non_interactive {
+        Box::new(non_interactive_prompts())
+    } else if let Some(answers) = &cli.answers {
+        Box::new(ScriptedPrompts::from_file(answers)?)
     } else {
-        get_root_path().as_path().join(Path::new("ssl/"))
+        Box::new(DialoguerPrompts)
+    };
+    let passphrase = cli.config_passphrase.source();
+    match cli.action.unwrap_or(Action::Generate(Box::default())) {
+        Action::Generate(args) => {
+            if let Some(parent) = config_path.parent() {
+                create_dir_all(parent).await?;
+            }
+            generate_config_from_mnemonic(
+                GenerateConfig {
+                    output_path: Some(config_path),
+                    mnemonics: args.mnemonic.sources(),
+                    passphrase: args.passphrase.source(),
+                    fullnode_ws_host: args.config.fullnode_ws_host,
+                    fullnode_ws_port: args.config.fullnode_ws_port,
+                    fullnode_rpc_host: args.config.fullnode_rpc_host,
+                    fullnode_rpc_port: args.config.fullnode_rpc_port,
+                    fullnode_ssl: args.config.fullnode_ssl,
+                    network: args.config.network,
+                    launcher_id: args.launcher_id.map(Bytes32::from),
+                    payout_address: args.config.payout_address,
+                    plot_directories: args.config.plot_directories,
+                    additional_headers: None,
+                    non_interactive: cli.non_interactive,
+                    overwrite: if args.force {
+                        Some(true)
+                    } else if args.no_overwrite {
+                        Some(false)
+                    } else {
+                        None
+                    },
+                    merge: args.merge,
+                    keys: args.secret_keys.exported_keys()?,
+                    chia_root: args
+                        .chia_root
+                        .map(|root| root.unwrap_or_else(default_chia_root)),
+                    skip_check: args.skip_check,
+                    scan: args.scan.try_into()?,
+                    backup_retention: cli.backup_retention,
+                    encrypt: if args.encrypt {
+                        Some(
+                            cli.config_passphrase
+                                .source()
+                                .unwrap_or(SecretSource::Prompt),
+                        )
+                    } else {
+                        None
+                    },
+                },
+                prompts.as_ref(),
+            )
+            .await?;
+        }
+        Action::Edit(args) => {
+            edit_config(
+                EditConfig {
+                    config_path,
+                    backup_retention: cli.backup_retention,
+                    fullnode_ws_host: args.fullnode_ws_host,
+                    fullnode_ws_port: args.fullnode_ws_port,
+                    fullnode_rpc_host: args.fullnode_rpc_host,
+                    fullnode_rpc_port: args.fullnode_rpc_port,
+                    fullnode_ssl: args.fullnode_ssl,
+                    network: args.network,
+                    payout_address: args.payout_address,
+                    plot_directories: args.plot_directories,
+                },
+                prompts.as_ref(),
+            )?;
+        }
+        Action::Validate => {
+            let mut config = Config::try_from(&config_path)?;
+            decrypt_if_encrypted(&mut config, passphrase.as_ref(), prompts.as_ref())?;
+            let issues = validate_config(&config);
+            if !issues.is_empty() {
+                return Err(InvalidConfig { issues }.into());
+            }
+            info!("Config at {config_path:?} is valid");
+        }
+        Action::Show(args) => {
+            let mut config = Config::try_from(&config_path)?;
+            decrypt_if_encrypted(&mut config, passphrase.as_ref(), prompts.as_ref())?;
+            let value = if args.redact {
+                config.to_redacted_value()?
+            } else {
+                serde_yaml::to_value(&config).map_err(|e| Error::other(format!("{:?}", e)))?
+            };
+            match args.format {
+                OutputFormat::Yaml => print!(
+                    "{}",
+                    serde_yaml::to_string(&value).map_err(|e| Error::other(format!("{:?}", e)))?
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&value)
+                        .map_err(|e| Error::other(format!("{:?}", e)))?
+                ),
+            }
+        }
+        Action::Key { action } => match action {
+            KeyAction::Add(args) => {
+                add_keys(
+                    AddKeyConfig {
+                        config_path,
+                        backup_retention: cli.backup_retention,
+                        mnemonics: args.mnemonic.sources(),
+                        passphrase: args.passphrase.source(),
+                        launcher_id: args.launcher_id.map(Bytes32::from),
+                        scan: args.scan.try_into()?,
+                        additional_headers: None,
+                        non_interactive: cli.non_interactive,
+                    },
+                    prompts.as_ref(),
+                )
+                .await?;
+            }
+            KeyAction::Export(args) => {
+                export_keys(
+                    ExportKeyConfig {
+                        output_path: args.output,
+                        config_path: args.from_config.then_some(config_path),
+                        config_passphrase: passphrase,
+                        mnemonics: args.mnemonic.sources(),
+                        passphrase: args.passphrase.source(),
+                        owner_key_indexes: index_range(
+                            "--owner-key-start",
+                            "--owner-key-end",
+                            args.owner_key_start,
+                            args.owner_key_end,
+                        )?,
+                        launcher_ids: args.launcher_ids.into_iter().map(Bytes32::from).collect(),
+                        bundle_passphrase: args.bundle_passphrase.source(),
+                    },
+                    prompts.as_ref(),
+                )?;
+            }
+            KeyAction::Import(args) => {
+                import_key_file(
+                    ImportKeyConfig {
+                        config_path,
+                        backup_retention: cli.backup_retention,
+                        bundle_path: args.bundle,
+                        bundle_passphrase: args.bundle_passphrase.source(),
+                        launcher_ids: args.launcher_ids.into_iter().map(Bytes32::from).collect(),
+                        additional_headers: None,
+                    },
+                    prompts.as_ref(),
+                )
+                .await?;
+            }
+            KeyAction::Remove { key } => {
+                let mut config = Config::try_from(&config_path)?;
+                remove_keys(&mut config, KeySelector::try_from(key.as_str())?)?;
+                save_config(&config, &config_path, cli.backup_retention)?;
+                info!("Saved Config to {config_path:?}");
+            }
+            KeyAction::List => {
+                let mut config = Config::try_from(&config_path)?;
+                decrypt_if_encrypted(&mut config, passphrase.as_ref(), prompts.as_ref())?;
+                for key in list_keys(&config) {
+                    println!(
+                        "{} {} {} {}",
+                        key.fingerprint,
+                        key.farmer_public_key,
+                        key.launcher_id
+                            .map(|l| l.to_string())
+                            .unwrap_or_else(|| String::from("(no launcher_id)")),
+                        key.pool_url.unwrap_or_default()
+                    );
+                }
+            }
+        },
+        Action::Encrypt => {
+            let mut config = Config::try_from(&config_path)?;
+            let passphrase = read_config_passphrase(passphrase.as_ref(), true, prompts.as_ref())?;
+            encrypt_farmer_info(&mut config, &passphrase)?;
+            save_config(&config, &config_path, cli.backup_retention)?;
+            info!("Encrypted farmer_info in {config_path:?}");
+        }
+        Action::Decrypt => {
+            let mut config = Config::try_from(&config_path)?;
+            let passphrase = read_config_passphrase(passphrase.as_ref(), false, prompts.as_ref())?;
+            decrypt_farmer_info(&mut config, &passphrase)?;
+            save_config(&config, &config_path, cli.backup_retention)?;
+            info!("Decrypted farmer_info in {config_path:?}");
+        }
+        Action::Restore { backup, yes } => {
+            //Newest first so 1 is always the most recent backup
+            let backups: Vec<PathBuf> = list_backups(&config_path)?.into_iter().rev().collect();
+            let Some(backup) = backup else {
+                if backups.is_empty() {
+                    println!("No backups found for {config_path:?}");
+                }
+                for (index, backup) in backups.iter().enumerate() {
+                    println!("{}: {}", index + 1, backup.display());
+                }
+                return Ok(());
+            };
+            //Nothing can confirm the restore without a terminal
+            if cli.non_interactive && !yes {
+                return Err(MissingInputs {
+                    fields: vec!["--yes"],
+                }
+                .into());
+            }
+            let backup_path = match backup.parse::<usize>() {
+                Ok(index) => backups.get(index.wrapping_sub(1)).cloned().ok_or_else(|| {
+                    Error::new(ErrorKind::NotFound, format!("No backup number {index}"))
+                })?,
+                Err(_) => PathBuf::from(backup),
+            };
+            if let Some(entries) = restore_changes(&config_path, &backup_path)? {
+                if entries.is_empty() {
+                    println!("No differences");
+                }
+                for entry in entries {
+                    println!("{entry}");
+                }
+            }
+            if !yes && !prompts.overwrite(&config_path)? {
+                return Err(Error::new(
+                    ErrorKind::Interrupted,
+                    format!("Not restoring {backup_path:?}"),
+                ));
+            }
+            restore_backup(&config_path, &backup_path, cli.backup_retention)?;
+            info!("Restored {backup_path:?} to {config_path:?}");
+        }
+        Action::Ssl { action } => match action {
+            SslAction::Setup {
+                chia_root,
+                ssl_root,
+            } => {
+                let ssl_root = ssl_root.unwrap_or_else(|| get_root_path().join("ssl"));
+                setup_ssl(&chia_root.unwrap_or_else(default_chia_root), &ssl_root)?;
+                if config_path.exists() {
+                    let mut config = Config::try_from(&config_path)?;
+                    config.ssl_root_path = Some(ssl_root.to_string_lossy().to_string());
+                    save_config(&config, &config_path, cli.backup_retention)?;
+                    info!("Set ssl_root_path to {ssl_root:?} in {config_path:?}");
+                }
+            }
+            SslAction::Check { ssl_root } => {
+                let ssl_root = ssl_root_for(&config_path, ssl_root)?;
+                let (reports, issues) = check_ssl(&ssl_root);
+                for report in reports {
+                    println!("{}", report.path.display());
+                    println!("    subject:   {}", report.subject);
+                    println!("    issuer:    {}", report.issuer);
+                    println!(
+                        "    valid:     {} to {}{}",
+                        report.not_before,
+                        report.not_after,
+                        if report.expired { " (expired)" } else { "" }
+                    );
+                    println!(
+                        "    key:       {} {}",
+                        match report.key_matches {
+                            Some(true) => "matches",
+                            Some(false) => "does not match",
+                            None => "unreadable",
+                        },
+                        report.key_path.display()
+                    );
+                }
+                if !issues.is_empty() {
+                    return Err(InvalidConfig { issues }.into());
+                }
+                info!("SSL files in {ssl_root:?} are valid");
+            }
+            SslAction::Regenerate {
+                ca,
+                ssl_root,
+                skip_rpc_check,
+            } => {
+                let ssl_root = ssl_root_for(&config_path, ssl_root)?;
+                let backup_path = regenerate_ssl(&ssl_root, ca.as_deref(), cli.backup_retention)?;
+                if skip_rpc_check || !config_path.exists() {
+                    return Ok(());
+                }
+                let mut config = Config::try_from(&config_path)?;
+                config.ssl_root_path = Some(ssl_root.to_string_lossy().to_string());
+                if let Err(e) = check_rpc_connection(&config, &None).await {
+                    return Err(Error::new(
+                        e.kind(),
+                        match backup_path {
+                            Some(backup_path) => {
+                                format!("{e}, the previous certs are kept in {backup_path:?}")
+                            }
+                            None => e.to_string(),
+                        },
+                    ));
+                }
+            }
+        },
+        Action::Check { timeout } => {
+            let config = Config::try_from(&config_path)?;
+            let reports = check_connectivity(&config, Duration::from_secs(timeout)).await;
+            for report in &reports {
+                println!("{report}");
+            }
+            first_failure(&reports)?;
+            info!("Fullnode is reachable with the settings in {config_path:?}");
+        }
+        Action::Diff { old, new } => {
+            let entries = diff_configs(&Config::try_from(&old)?, &Config::try_from(&new)?);
+            if entries.is_empty() {
+                println!("No differences");
+            }
+            for entry in entries {
+                println!("{entry}");
+            }
+        }
     }
+    Ok(())
 }
 
-pub(crate) fn get_config_path() -> PathBuf {
-    get_root_path()
-        .as_path()
-        .join(Path::new("fast_farmer.yaml"))
+//The --ssl-root override, else the ssl_root_path of the config, else the default ssl root
+fn ssl_root_for(config_path: &Path, ssl_root: Option<PathBuf>) -> Result<PathBuf, Error> {
+    Ok(if let Some(ssl_root) = ssl_root {
+        ssl_root
+    } else if config_path.exists() {
+        get_ssl_root_path(&Config::try_from(config_path)?)
+    } else {
+        get_ssl_root_path(&Config::default())
+    })
 }
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    #[arg(short, long, value_name = "FILE")]
+    #[arg(short, long, value_name = "FILE", global = true)]
     pub config: Option<String>,
+    /// Never prompt, fail listing every missing value instead
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+    /// Answer prompts from a YAML answer file instead of the terminal
+    #[arg(
+        long,
+        value_name = "FILE",
+        global = true,
+        conflicts_with = "non_interactive"
+    )]
+    pub answers: Option<String>,
+    #[command(flatten)]
+    pub config_passphrase: ConfigPassphraseArgs,
+    /// Number of timestamped backups kept when a config is overwritten, 0 disables backups
+    #[arg(long, global = true, default_value_t = DEFAULT_BACKUP_RETENTION)]
+    pub backup_retention: usize,
+    #[command(subcommand)]
+    pub action: Option<Action>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Action {
+    /// Generate a new config from a mnemonic (default when no command is given)
+    Generate(Box<GenerateArgs>),
+    /// Edit an existing config, prompting with the current values
+    Edit(ConfigArgs),
+    /// Check an existing config, listing every problem with its YAML path
+    Validate,
+    /// Print an existing config
+    Show(ShowArgs),
+    /// Add, remove or list the keys in an existing config
+    #[command(alias = "keys")]
+    Key {
+        #[command(subcommand)]
+        action: KeyAction,
+    },
+    /// Encrypt the secret keys in farmer_info with a passphrase
+    Encrypt,
+    /// Decrypt the secret keys in farmer_info, storing them as plain hex again
+    Decrypt,
+    /// List the backups of the config, or restore one after showing what would change
+    Restore {
+        /// Backup number from the list (1 is the newest) or path of the backup to restore
+        backup: Option<String>,
+        /// Restore without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Check DNS, TCP, TLS and RPC access to the fullnode, reporting the stage that fails
+    Check {
+        /// Seconds to wait for each stage
+        #[arg(long, default_value_t = DEFAULT_CHECK_TIMEOUT.as_secs())]
+        timeout: u64,
+    },
+    /// Manage the SSL certificates used to connect to the fullnode
+    Ssl {
+        #[command(subcommand)]
+        action: SslAction,
+    },
+    /// Print the field level differences between two configs, secrets are shown as fingerprints
+    Diff {
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SslAction {
+    /// Copy the Chia CA into a FastFarmer SSL directory, generate the farmer certs and verify them
+    Setup {
+        /// Chia root, ssl or ssl/ca directory holding the CA, defaults to CHIA_ROOT or ~/.chia/mainnet
+        #[arg(long, value_name = "DIR")]
+        chia_root: Option<PathBuf>,
+        /// Directory the certs are written to, defaults to ~/.config/fast_farmer/ssl
+        #[arg(long, value_name = "DIR")]
+        ssl_root: Option<PathBuf>,
+    },
+    /// Back up the SSL directory and sign a new farmer certificate, then test the RPC connection
+    Regenerate {
+        /// Chia root, ssl or ssl/ca directory of a new CA to sign the farmer certificate with
+        #[arg(long, value_name = "DIR")]
+        ca: Option<PathBuf>,
+        /// Directory to regenerate instead of the ssl_root_path of the config
+        #[arg(long, value_name = "DIR")]
+        ssl_root: Option<PathBuf>,
+        /// Do not connect to the fullnode after regenerating
+        #[arg(long)]
+        skip_rpc_check: bool,
+    },
+    /// Inspect the CA and farmer certificates, reporting expiry, key match and signature problems
+    Check {
+        /// Directory to check instead of the ssl_root_path of the config
+        #[arg(long, value_name = "DIR")]
+        ssl_root: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum KeyAction {
+    /// Derive the keys of another mnemonic, discover its PlotNFTs and add them to the config
+    Add(KeyAddArgs),
+    /// Write the farming secret keys and launcher ids to an encrypted key bundle, works offline
+    Export(KeyExportArgs),
+    /// Add the keys and PlotNFTs of an encrypted key bundle to the config
+    Import(KeyImportArgs),
+    /// Remove every entry belonging to a farmer public key or launcher id
+    Remove {
+        #[arg(value_name = "FARMER_PUBLIC_KEY_OR_LAUNCHER_ID")]
+        key: String,
+    },
+    /// List the keys and PlotNFTs in the config
+    List,
+}
+
+#[derive(Args, Debug)]
+pub struct KeyAddArgs {
+    #[command(flatten)]
+    mnemonic: MnemonicArgs,
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+    #[arg(short = 'l', long)]
+    launcher_id: Option<String>,
+    #[command(flatten)]
+    scan: ScanArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct KeyExportArgs {
+    #[command(flatten)]
+    mnemonic: MnemonicArgs,
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+    /// Export the farmer_info of the config instead of deriving keys from a mnemonic
+    #[arg(long, conflicts_with_all = ["mnemonic_files", "mnemonic_stdin", "mnemonic_envs", "passphrase", "passphrase_file", "passphrase_env"])]
+    from_config: bool,
+    /// Launcher id of a PlotNFT farmed with the keys, repeat for several PlotNFTs
+    #[arg(short = 'l', long = "launcher-id")]
+    launcher_ids: Vec<String>,
+    /// File the key bundle is written to
+    #[arg(short = 'O', long, value_name = "FILE")]
+    output: PathBuf,
+    /// First singleton owner key index exported for PlotNFT authentication
+    #[arg(long, default_value_t = ScanOptions::default().owner_key_indexes.start)]
+    owner_key_start: u32,
+    /// Singleton owner key index to stop exporting at (exclusive)
+    #[arg(long, default_value_t = ScanOptions::default().owner_key_indexes.end)]
+    owner_key_end: u32,
+    #[command(flatten)]
+    bundle_passphrase: BundlePassphraseArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct KeyImportArgs {
+    #[arg(value_name = "BUNDLE")]
+    bundle: PathBuf,
+    /// Launcher id of a PlotNFT to farm in addition to the ones in the bundle, repeat for several
+    #[arg(short = 'l', long = "launcher-id")]
+    launcher_ids: Vec<String>,
+    #[command(flatten)]
+    bundle_passphrase: BundlePassphraseArgs,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct BundlePassphraseArgs {
+    /// Read the passphrase protecting the key bundle from a file
+    #[arg(long, value_name = "FILE", conflicts_with = "bundle_passphrase_env")]
+    bundle_passphrase_file: Option<PathBuf>,
+    /// Read the passphrase protecting the key bundle from an environment variable
+    #[arg(long, value_name = "VAR")]
+    bundle_passphrase_env: Option<String>,
+}
+impl BundlePassphraseArgs {
+    fn source(&self) -> Option<SecretSource> {
+        if let Some(path) = &self.bundle_passphrase_file {
+            Some(SecretSource::File(path.clone()))
+        } else {
+            self.bundle_passphrase_env.clone().map(SecretSource::Env)
+        }
+    }
+}
+
+#[derive(Args, Debug, Default)]
+#[command(next_help_heading = "Secret Keys")]
+pub struct SecretKeyArgs {
+    /// Farmer secret key in hex, builds the config without a mnemonic
+    #[arg(long, requires = "pool_secret_key", conflicts_with_all = ["mnemonic_files", "mnemonic_stdin", "mnemonic_envs"])]
+    farmer_secret_key: Option<String>,
+    /// Pool secret key in hex
+    #[arg(long, requires = "farmer_secret_key")]
+    pool_secret_key: Option<String>,
+    /// Singleton owner secret key of the PlotNFT in hex
+    #[arg(long, requires_all = ["farmer_secret_key", "auth_secret_key"])]
+    owner_secret_key: Option<String>,
+    /// Pool authentication secret key of the PlotNFT in hex
+    #[arg(long, requires = "owner_secret_key")]
+    auth_secret_key: Option<String>,
+}
+impl SecretKeyArgs {
+    fn exported_keys(&self) -> Result<Option<ExportedKeys>, Error> {
+        let (Some(farmer_secret_key), Some(pool_secret_key)) =
+            (&self.farmer_secret_key, &self.pool_secret_key)
+        else {
+            return Ok(None);
+        };
+        let mut owner_keys = vec![];
+        if let (Some(owner_secret_key), Some(auth_secret_key)) =
+            (&self.owner_secret_key, &self.auth_secret_key)
+        {
+            owner_keys.push(OwnerKeyPair {
+                index: None,
+                owner_secret_key: parse_secret_key(owner_secret_key)?,
+                auth_secret_key: parse_secret_key(auth_secret_key)?,
+            });
+        }
+        Ok(Some(ExportedKeys {
+            farmer_secret_key: parse_secret_key(farmer_secret_key)?,
+            pool_secret_key: Some(parse_secret_key(pool_secret_key)?),
+            owner_keys,
+        }))
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ConfigPassphraseArgs {
+    /// Read the passphrase protecting encrypted farmer_info from a file
+    #[arg(
+        long,
+        value_name = "FILE",
+        global = true,
+        conflicts_with = "config_passphrase_env"
+    )]
+    config_passphrase_file: Option<PathBuf>,
+    /// Read the passphrase protecting encrypted farmer_info from an environment variable
+    #[arg(long, value_name = "VAR", global = true)]
+    config_passphrase_env: Option<String>,
+}
+impl ConfigPassphraseArgs {
+    fn source(&self) -> Option<SecretSource> {
+        if let Some(path) = &self.config_passphrase_file {
+            Some(SecretSource::File(path.clone()))
+        } else {
+            self.config_passphrase_env.clone().map(SecretSource::Env)
+        }
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct MnemonicArgs {
+    /// Mnemonic file to derive keys from, repeat to add several keys
+    #[arg(short = 'm', long = "mnemonic-file")]
+    mnemonic_files: Vec<PathBuf>,
+    /// Read a mnemonic from stdin
+    #[arg(long)]
+    mnemonic_stdin: bool,
+    /// Read a mnemonic from an environment variable, repeat to add several keys
+    #[arg(long = "mnemonic-env", value_name = "VAR")]
+    mnemonic_envs: Vec<String>,
+}
+impl MnemonicArgs {
+    fn sources(&self) -> Vec<SecretSource> {
+        let mut sources: Vec<SecretSource> = self
+            .mnemonic_files
+            .iter()
+            .cloned()
+            .map(SecretSource::File)
+            .collect();
+        if self.mnemonic_stdin {
+            sources.push(SecretSource::Stdin);
+        }
+        sources.extend(self.mnemonic_envs.iter().cloned().map(SecretSource::Env));
+        sources
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct PassphraseArgs {
+    /// Prompt for the BIP39 passphrase (25th word) of the mnemonic with hidden input
+    #[arg(long, conflicts_with_all = ["passphrase_file", "passphrase_env"])]
+    passphrase: bool,
+    /// Read the BIP39 passphrase from a file
+    #[arg(long, value_name = "FILE", conflicts_with = "passphrase_env")]
+    passphrase_file: Option<PathBuf>,
+    /// Read the BIP39 passphrase from an environment variable
+    #[arg(long, value_name = "VAR")]
+    passphrase_env: Option<String>,
+}
+impl PassphraseArgs {
+    fn source(&self) -> Option<SecretSource> {
+        if self.passphrase {
+            Some(SecretSource::Prompt)
+        } else if let Some(path) = &self.passphrase_file {
+            Some(SecretSource::File(path.clone()))
+        } else {
+            self.passphrase_env.clone().map(SecretSource::Env)
+        }
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ConfigArgs {
     #[arg(short = 'f', long)]
     fullnode_ws_host: Option<String>,
     #[arg(short = 'p', long)]
@@ -115,278 +715,135 @@ pub struct Cli {
     payout_address: Option<String>,
     #[arg(short = 'd', long = "plot-directory")]
     plot_directories: Option<Vec<String>>,
-    #[arg(short = 'm', long)]
-    mnemonic_file: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct GenerateArgs {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    mnemonic: MnemonicArgs,
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
     #[arg(short = 'l', long)]
     launcher_id: Option<String>,
+    /// Overwrite an existing config without asking
+    #[arg(long, conflicts_with = "no_overwrite")]
+    force: bool,
+    /// Fail instead of asking when a config already exists
+    #[arg(long)]
+    no_overwrite: bool,
+    /// Update an existing config in place, keeping every value that is not changed
+    #[arg(long, conflicts_with_all = ["force", "no_overwrite"])]
+    merge: bool,
+    /// Encrypt the secret keys in farmer_info with a passphrase before saving
+    #[arg(long)]
+    encrypt: bool,
+    /// Do not check the connection to the fullnode before searching for PlotNFTs
+    #[arg(long)]
+    skip_check: bool,
+    /// Prefill hosts, ports, network, payout address, ssl and plot directories from a Chia install,
+    /// defaults to CHIA_ROOT or ~/.chia/mainnet
+    #[arg(long, value_name = "DIR", num_args = 0..=1)]
+    chia_root: Option<Option<PathBuf>>,
+    #[command(flatten)]
+    secret_keys: SecretKeyArgs,
+    #[command(flatten)]
+    scan: ScanArgs,
 }
 
-pub struct GenerateConfig {
-    pub output_path: Option<PathBuf>,
-    pub mnemonic_file: Option<String>,
-    pub fullnode_ws_host: Option<String>,
-    pub fullnode_ws_port: Option<u16>,
-    pub fullnode_rpc_host: Option<String>,
-    pub fullnode_rpc_port: Option<u16>,
-    pub fullnode_ssl: Option<String>,
-    pub network: Option<String>,
-    pub launcher_id: Option<Bytes32>,
-    pub payout_address: Option<String>,
-    pub plot_directories: Option<Vec<String>>,
-    pub additional_headers: Option<HashMap<String, String>>,
+#[derive(Args, Debug)]
+#[command(next_help_heading = "PlotNFT Scan")]
+pub struct ScanArgs {
+    /// First wallet derivation index to search for PlotNFTs
+    #[arg(long, default_value_t = ScanOptions::default().wallet_indexes.start)]
+    scan_start: u32,
+    /// Wallet derivation index to stop searching at (exclusive)
+    #[arg(long, default_value_t = ScanOptions::default().wallet_indexes.end)]
+    scan_end: u32,
+    /// Number of derivation indexes requested per page
+    #[arg(
+        long,
+        default_value_t = ScanOptions::default().page_size,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    scan_page_size: u32,
+    /// Keep scanning after the first page that contains a PlotNFT, collecting every PlotNFT of the key
+    #[arg(long, alias = "all-plotnfts")]
+    scan_all: bool,
+    /// Number of pages requested from the fullnode at the same time
+    #[arg(
+        long,
+        default_value_t = ScanOptions::default().concurrency as u16,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    scan_concurrency: u16,
+    /// First singleton owner key index to search for the PlotNFT owner key
+    #[arg(long, default_value_t = ScanOptions::default().owner_key_indexes.start)]
+    owner_key_start: u32,
+    /// Singleton owner key index to stop searching at (exclusive)
+    #[arg(long, default_value_t = ScanOptions::default().owner_key_indexes.end)]
+    owner_key_end: u32,
 }
-
-pub async fn generate_config_from_mnemonic(gen_settings: GenerateConfig) -> Result<Config, Error> {
-    //Check for Existing Config and prompt for override
-    if let Some(op) = &gen_settings.output_path {
-        if op.exists()
-            && !Confirm::new()
-                .with_prompt(format!(
-                    "An existing config exists at {:?}, would you like to override it? (Y/N)",
-                    op
-                ))
-                .interact()
-                .map_err(|e| {
-                    Error::new(
-                        ErrorKind::Interrupted,
-                        format!("Dialog Interrupted: {:?}", e),
-                    )
-                })?
-        {
-            return Err(Error::new(ErrorKind::Interrupted, "User Canceled"));
-        }
+//An empty range would silently search nothing, so the end has to be past the start
+fn index_range(start_arg: &str, end_arg: &str, start: u32, end: u32) -> Result<Range<u32>, Error> {
+    if end <= start {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{end_arg} ({end}) must be greater than {start_arg} ({start})"),
+        ));
     }
-    let mut config = Config::default();
-    let network = gen_settings
-        .network
-        .map(|v| {
-            if CONSENSUS_CONSTANTS_MAP.contains_key(&v) {
-                v
-            } else {
-                "mainnet".to_string()
-            }
+    Ok(start..end)
+}
+impl TryFrom<ScanArgs> for ScanOptions {
+    type Error = Error;
+    fn try_from(value: ScanArgs) -> Result<Self, Self::Error> {
+        Ok(ScanOptions {
+            wallet_indexes: index_range(
+                "--scan-start",
+                "--scan-end",
+                value.scan_start,
+                value.scan_end,
+            )?,
+            page_size: value.scan_page_size,
+            owner_key_indexes: index_range(
+                "--owner-key-start",
+                "--owner-key-end",
+                value.owner_key_start,
+                value.owner_key_end,
+            )?,
+            scan_all: value.scan_all,
+            concurrency: value.scan_concurrency as usize,
         })
-        .unwrap_or("mainnet".to_string());
-    config.selected_network = network;
-    //Prompt the User for Mnemonic to Generate needed Keys
-    let master_key = key_from_mnemonic(&prompt_for_mnemonic(gen_settings.mnemonic_file)?)?;
-    //Prompt for Payout Address, Will populate farmer and pool reward addresses
-    config.payout_address = prompt_for_payout_address(gen_settings.payout_address)?.to_string();
-    //Prompt for Node to connect the Farming Websocket
-    config.fullnode_ws_host =
-        prompt_for_farming_fullnode(gen_settings.fullnode_ws_host)?.to_string();
-    //If the User is not using the community node, ask for RPC info.
-    //This is used to update the status of the fullnode and to search for plot_nft info
-    //If using the migrate functions of the farmer this is also where the push_tx call will go
-    config.fullnode_rpc_host = if let Some(host) = gen_settings.fullnode_rpc_host {
-        host
-    } else if "chia-proxy.evergreenminer-prod.com" == config.fullnode_ws_host {
-        "chia-proxy.evergreenminer-prod.com".to_string()
-    } else {
-        prompt_for_rpc_fullnode(None)?
-    };
-    //Farming Port, typically 443 for community and 8444 for local node
-    config.fullnode_ws_port = if let Some(port) = gen_settings.fullnode_ws_port {
-        port
-    } else if "chia-proxy.evergreenminer-prod.com" == config.fullnode_ws_host {
-        443
-    } else if "localhost" == config.fullnode_ws_host {
-        8444
-    } else {
-        prompt_for_farming_port(None)?
-    };
-    //RPC Port, typically 443 for community and 8555 for local node
-    config.fullnode_rpc_port = if let Some(port) = gen_settings.fullnode_rpc_port {
-        port
-    } else if "chia-proxy.evergreenminer-prod.com" == config.fullnode_rpc_host {
-        443
-    } else if "localhost" == config.fullnode_rpc_host {
-        8555
-    } else {
-        prompt_for_rpc_port(None)?
-    };
-    //For community node this can be left blank as it will generate the required certs.
-    //For local hosted nodes, it is recommended to create a folder and copy the "ssl/ca"
-    //from your chia install to the created folder. This will allow FastFarmer to connect without
-    //conflicting with the Chia Farmer that runs with the GUI or any other farmers.
-    config.ssl_root_path = if "chia-proxy.evergreenminer-prod.com" == config.fullnode_ws_host {
-        None
-    } else {
-        prompt_for_ssl_path(gen_settings.fullnode_ssl)?
-    };
-    //This tool is used to generate GigahorseCongigs.
-    //For regular DruidGarden config please use the Open Source version of FastFarmer
-    config.harvester_configs.gigahorse = Some(GigahorseHarvesterConfig {
-        plot_directories: if let Some(dirs) = gen_settings.plot_directories {
-            dirs
-        } else {
-            prompt_for_plot_directories()?
-        },
-        parallel_read: true,
-        plot_search_depth: 2,
-        max_cpu_cores: -1,
-        max_cuda_devices: -1,
-        max_opencl_devices: -1,
-        cuda_device_list: vec![],
-        opencl_device_list: vec![],
-        recompute_host: "".to_string(),
-        recompute_port: 0,
-    });
-    //Always check/generate the SSL, this will not overwrite existing files
-    if let Some(ssl_path) = &config.ssl_root_path {
-        create_all_ssl(Path::new(ssl_path), false)?;
-    } else {
-        let ssl_path = get_ssl_root_path(&config);
-        create_all_ssl(&ssl_path, false)?;
-        config.ssl_root_path = Some(ssl_path.to_string_lossy().to_string());
     }
-    //If the RPC connection fails it is likely one of the below:
-    //1. The wrong SSL path was given or the ca files don't match the Fullnode.
-    //2. The self_hostname field in the Chia Fullnode config is not set to 0.0.0.0 and you are trying to connect remotely
-    //3. The Port or Hostname fields are not set up correctly to match the Fullnode, verify which ports the RPC and WS are running on in the Chia config
-    let client = rpc_client_from_config(&config, &gen_settings.additional_headers);
-    let mut page = 0;
-    let mut plotnfts = vec![];
-    //Depending on how many "Claims" have happened with your PlotNFT this process can take some time.
-    //
-    if let Some(launcher_id) = prompt_for_launcher_id(gen_settings.launcher_id)? {
-        info!("Searching for NFT with LauncherID: {launcher_id}");
-        if let Some(plotnft) = get_plotnft_by_launcher_id(client.clone(), &launcher_id).await? {
-            plotnfts.push(plotnft);
-        } else {
-            return Err(Error::new(
-                ErrorKind::NotFound,
-                "Failed to find a plotNFT with LauncherID: {launcher_id}",
-            ));
-        }
-    } else {
-        info!("No LauncherID Specified, Searching for PlotNFTs...");
-        while page < 50 && plotnfts.is_empty() {
-            let mut puzzle_hashes = vec![];
-            for index in page * 50..(page + 1) * 50 {
-                let wallet_sk =
-                    master_sk_to_wallet_sk_unhardened(&master_key, index).map_err(|e| {
-                        Error::new(
-                            ErrorKind::InvalidInput,
-                            format!("Failed to parse Wallet SK: {:?}", e),
-                        )
-                    })?;
-                let pub_key: Bytes48 = wallet_sk.sk_to_pk().to_bytes().into();
-                puzzle_hashes.push(puzzle_hash_for_pk(&pub_key)?);
-                let hardened_wallet_sk =
-                    master_sk_to_wallet_sk(&master_key, index).map_err(|e| {
-                        Error::new(
-                            ErrorKind::InvalidInput,
-                            format!("Failed to parse Wallet SK: {:?}", e),
-                        )
-                    })?;
-                let pub_key: Bytes48 = hardened_wallet_sk.sk_to_pk().to_bytes().into();
-                puzzle_hashes.push(puzzle_hash_for_pk(&pub_key)?);
-            }
-            plotnfts.extend(scrounge_for_plotnfts(client.clone(), &puzzle_hashes).await?);
-            page += 1;
-        }
-    }
-    for plot_nft in plotnfts {
-        config.pool_info.push(PoolWalletConfig {
-            launcher_id: plot_nft.launcher_id,
-            pool_url: plot_nft.pool_state.pool_url.unwrap_or_default(),
-            target_puzzle_hash: plot_nft.pool_state.target_puzzle_hash,
-            payout_instructions: config.payout_address.clone(),
-            p2_singleton_puzzle_hash: launcher_id_to_p2_puzzle_hash(
-                &plot_nft.launcher_id,
-                plot_nft.delay_time as u64,
-                &plot_nft.delay_puzzle_hash,
-            )?,
-            owner_public_key: plot_nft.pool_state.owner_pubkey,
-            difficulty: None,
-        });
-        let mut owner_key = None;
-        let mut auth_key = None;
-        for i in 0..150 {
-            let key = master_sk_to_singleton_owner_sk(&master_key, i).unwrap();
-            let pub_key: Bytes48 = key.sk_to_pk().to_bytes().into();
-            if pub_key == plot_nft.pool_state.owner_pubkey {
-                let a_key = master_sk_to_pooling_authentication_sk(&master_key, i, 0).unwrap();
-                owner_key = Some(key.into());
-                auth_key = Some(a_key.into());
-                break;
-            }
-        }
-        if let Some(info) = config.farmer_info.iter_mut().find(|f| {
-            if let Some(l) = &f.launcher_id {
-                l == &plot_nft.launcher_id
-            } else {
-                false
-            }
-        }) {
-            info.farmer_secret_key = master_sk_to_farmer_sk(&master_key)?.into();
-            info.launcher_id = Some(plot_nft.launcher_id);
-            info.pool_secret_key = Some(master_sk_to_pool_sk(&master_key)?.into());
-            info.owner_secret_key = owner_key;
-            info.auth_secret_key = auth_key;
-        } else {
-            config.farmer_info.push(FarmingInfo {
-                farmer_secret_key: master_sk_to_farmer_sk(&master_key)?.into(),
-                launcher_id: Some(plot_nft.launcher_id),
-                pool_secret_key: Some(master_sk_to_pool_sk(&master_key)?.into()),
-                owner_secret_key: owner_key,
-                auth_secret_key: auth_key,
-            });
+}
+impl Default for ScanArgs {
+    fn default() -> Self {
+        let options = ScanOptions::default();
+        Self {
+            scan_start: options.wallet_indexes.start,
+            scan_end: options.wallet_indexes.end,
+            scan_page_size: options.page_size,
+            scan_all: options.scan_all,
+            scan_concurrency: options.concurrency as u16,
+            owner_key_start: options.owner_key_indexes.start,
+            owner_key_end: options.owner_key_indexes.end,
         }
     }
-    if config.farmer_info.is_empty() {
-        warn!("No PlotNFT Found");
-        config.farmer_info.push(FarmingInfo {
-            farmer_secret_key: master_sk_to_farmer_sk(&master_key)?.into(),
-            launcher_id: None,
-            pool_secret_key: Some(master_sk_to_pool_sk(&master_key)?.into()),
-            owner_secret_key: None,
-            auth_secret_key: None,
-        });
-    }
-    if let Some(op) = &gen_settings.output_path {
-        config.save_as_yaml(op)?;
-    }
-    Ok(config)
 }
 
-pub(crate) fn rpc_client_from_config(
-    config: &Config,
-    headers: &Option<HashMap<String, String>>,
-) -> Arc<FullnodeClient> {
-    Arc::new(FullnodeClient::new(
-        &config.fullnode_rpc_host,
-        config.fullnode_rpc_port,
-        600,
-        if is_community_node(config) {
-            None
-        } else {
-            config.ssl_root_path.clone().map(|s| ClientSSLConfig {
-                ssl_crt_path: Path::new(&s)
-                    .join(PRIVATE_CRT)
-                    .to_string_lossy()
-                    .to_string(),
-                ssl_key_path: Path::new(&s)
-                    .join(PRIVATE_KEY)
-                    .to_string_lossy()
-                    .to_string(),
-                ssl_ca_crt_path: Path::new(&s)
-                    .join(CA_PRIVATE_CRT)
-                    .to_string_lossy()
-                    .to_string(),
-            })
-        },
-        headers,
-    ))
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum OutputFormat {
+    #[default]
+    Yaml,
+    Json,
 }
 
-pub fn is_community_node(config: &Config) -> bool {
-    [
-        "chia-proxy.evergreenminer-prod.com",
-        "chia-proxy.galactechs.com",
-        "chia-proxy-testnet11.evergreenminer-prod.com",
-        "chia-proxy-testnet11.galactechs.com",
-    ]
-    .contains(&config.fullnode_rpc_host.to_ascii_lowercase().trim())
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// Replace every secret key with its public key fingerprint
+    #[arg(long)]
+    redact: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Yaml)]
+    format: OutputFormat,
 }
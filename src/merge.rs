@@ -0,0 +1,145 @@
+use crate::config::Config;
+use crate::diff::{diff_configs, farming_info_path, pool_info_path, DiffEntry};
+use log::info;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Updated,
+    Removed,
+    Kept,
+}
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "Added"),
+            ChangeKind::Updated => write!(f, "Updated"),
+            ChangeKind::Removed => write!(f, "Removed"),
+            ChangeKind::Kept => write!(f, "Kept"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    pub field: String,
+    pub kind: ChangeKind,
+}
+
+const TOP_LEVEL_FIELDS: [&str; 9] = [
+    "selected_network",
+    "ssl_root_path",
+    "fullnode_ws_host",
+    "fullnode_ws_port",
+    "fullnode_rpc_host",
+    "fullnode_rpc_port",
+    "payout_address",
+    "harvester_configs",
+    "metrics",
+];
+
+//Lists the differences diff_configs finds between the original and the merged config, plus every
+//top level field and entry of the merged config without a difference as kept
+pub fn summarize_changes(old: &Config, new: &Config) -> Vec<ConfigChange> {
+    let entries = diff_configs(old, new);
+    let mut changes: Vec<ConfigChange> = entries
+        .iter()
+        .map(|entry| ConfigChange {
+            field: entry.path().to_string(),
+            kind: match entry {
+                DiffEntry::Added { .. } => ChangeKind::Added,
+                DiffEntry::Removed { .. } => ChangeKind::Removed,
+                DiffEntry::Changed { .. } => ChangeKind::Updated,
+            },
+        })
+        .collect();
+    let unchanged = |field: &str| {
+        !entries
+            .iter()
+            .any(|entry| entry.path() == field || entry.path().starts_with(&format!("{field}.")))
+    };
+    let fields = TOP_LEVEL_FIELDS
+        .iter()
+        .map(|f| f.to_string())
+        .chain(new.farmer_info.iter().map(farming_info_path))
+        .chain(new.pool_info.iter().map(|p| pool_info_path(&p.launcher_id)));
+    for field in fields {
+        if unchanged(&field) {
+            changes.push(ConfigChange {
+                field,
+                kind: ChangeKind::Kept,
+            });
+        }
+    }
+    changes
+}
+
+pub fn log_config_changes(changes: &[ConfigChange]) {
+    info!("Merge Summary:");
+    for kind in [
+        ChangeKind::Added,
+        ChangeKind::Updated,
+        ChangeKind::Removed,
+        ChangeKind::Kept,
+    ] {
+        for change in changes.iter().filter(|c| c.kind == kind) {
+            info!("  {kind}: {}", change.field);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{farming_info, pool};
+
+    fn fields(changes: &[ConfigChange], kind: ChangeKind) -> Vec<&str> {
+        changes
+            .iter()
+            .filter(|c| c.kind == kind)
+            .map(|c| c.field.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn summarizes_the_differences_of_diff_configs() {
+        let old = Config {
+            farmer_info: vec![farming_info(1, None), farming_info(2, Some(20))],
+            pool_info: vec![pool(20)],
+            ..Default::default()
+        };
+        let mut new = Config {
+            fullnode_rpc_port: old.fullnode_rpc_port + 1,
+            farmer_info: vec![farming_info(1, Some(10)), farming_info(2, Some(20))],
+            pool_info: vec![pool(10), pool(20)],
+            ..old.clone()
+        };
+        new.pool_info[1].pool_url = "https://other.example.com".to_string();
+        let changes = summarize_changes(&old, &new);
+        let launcher = |byte: u8| crate::test_util::bytes32(byte).to_string();
+        assert_eq!(
+            fields(&changes, ChangeKind::Added),
+            vec![
+                format!("farmer_info[{}]", launcher(10)),
+                format!("pool_info[{}]", launcher(10))
+            ]
+        );
+        assert_eq!(
+            fields(&changes, ChangeKind::Updated),
+            vec![
+                "fullnode_rpc_port".to_string(),
+                format!("pool_info[{}].pool_url", launcher(20))
+            ]
+        );
+        assert_eq!(
+            fields(&changes, ChangeKind::Removed),
+            vec![farming_info_path(&old.farmer_info[0])]
+        );
+        let kept = fields(&changes, ChangeKind::Kept);
+        assert!(kept.contains(&"payout_address"));
+        assert!(kept.contains(&format!("farmer_info[{}]", launcher(20)).as_str()));
+        assert!(!kept.contains(&"fullnode_rpc_port"));
+        assert!(!kept.contains(&format!("pool_info[{}]", launcher(20)).as_str()));
+    }
+}
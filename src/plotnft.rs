@@ -0,0 +1,134 @@
+use crate::keys::wallet_puzzle_hashes;
+use blst::min_pk::SecretKey;
+use dg_xch_cli::wallets::plotnft_utils::{get_plotnft_by_launcher_id, scrounge_for_plotnfts};
+use dg_xch_clients::rpc::full_node::FullnodeClient;
+use dg_xch_core::blockchain::sized_bytes::Bytes32;
+use dg_xch_core::config::PoolWalletConfig;
+use dg_xch_core::plots::PlotNft;
+use dg_xch_puzzles::clvm_puzzles::launcher_id_to_p2_puzzle_hash;
+use log::info;
+use std::io::{Error, ErrorKind};
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+pub async fn find_plotnft_by_launcher_id(
+    client: Arc<FullnodeClient>,
+    launcher_id: &Bytes32,
+) -> Result<PlotNft, Error> {
+    info!("Searching for NFT with LauncherID: {launcher_id}");
+    get_plotnft_by_launcher_id(client, launcher_id)
+        .await?
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("Failed to find a plotNFT with LauncherID: {launcher_id}"),
+            )
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    //Wallet derivation indexes to search, both hardened and unhardened keys are checked
+    pub wallet_indexes: Range<u32>,
+    pub page_size: u32,
+    //Singleton owner key indexes searched to find the owner and auth keys of a PlotNFT
+    pub owner_key_indexes: Range<u32>,
+    //Keep scanning after the first page that yields a PlotNFT
+    pub scan_all: bool,
+    //Number of pages requested from the fullnode at the same time
+    pub concurrency: usize,
+}
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            wallet_indexes: 0..2500,
+            page_size: 50,
+            owner_key_indexes: 0..150,
+            scan_all: false,
+            concurrency: 1,
+        }
+    }
+}
+impl ScanOptions {
+    fn pages(&self) -> Vec<Range<u32>> {
+        let page_size = self.page_size.max(1);
+        let mut pages = vec![];
+        let mut start = self.wallet_indexes.start;
+        while start < self.wallet_indexes.end {
+            let end = start.saturating_add(page_size).min(self.wallet_indexes.end);
+            pages.push(start..end);
+            start = end;
+        }
+        pages
+    }
+}
+
+//Scans the wallet puzzle hashes of the key page by page, by default stopping at the first page with a PlotNFT
+pub async fn scan_for_plotnfts(
+    client: Arc<FullnodeClient>,
+    master_key: &SecretKey,
+    options: &ScanOptions,
+) -> Result<Vec<PlotNft>, Error> {
+    info!("No LauncherID Specified, Searching for PlotNFTs...");
+    let pages = options.pages();
+    let total = pages.len();
+    let mut plotnfts = vec![];
+    for (batch_index, batch) in pages.chunks(options.concurrency.max(1)).enumerate() {
+        let mut requests = JoinSet::new();
+        for (offset, indexes) in batch.iter().enumerate() {
+            let page = batch_index * options.concurrency.max(1) + offset;
+            let puzzle_hashes = wallet_puzzle_hashes(master_key, indexes.clone())?;
+            let client = client.clone();
+            requests
+                .spawn(async move { (page, scrounge_for_plotnfts(client, &puzzle_hashes).await) });
+        }
+        let mut results = vec![];
+        while let Some(result) = requests.join_next().await {
+            let (page, found) = result.map_err(|e| Error::other(format!("{:?}", e)))?;
+            results.push((page, found?));
+        }
+        //Keep the results in derivation order no matter which request finished first
+        results.sort_by_key(|(page, _)| *page);
+        for (page, found) in results {
+            info!(
+                "Scanned page {}/{total} (indexes {:?}), found {} PlotNFT(s)",
+                page + 1,
+                pages[page],
+                found.len()
+            );
+            //The same PlotNFT can be found from more than one puzzle hash
+            for plot_nft in found {
+                if !plotnfts
+                    .iter()
+                    .any(|p: &PlotNft| p.launcher_id == plot_nft.launcher_id)
+                {
+                    plotnfts.push(plot_nft);
+                }
+            }
+        }
+        if !options.scan_all && !plotnfts.is_empty() {
+            break;
+        }
+    }
+    Ok(plotnfts)
+}
+
+pub fn pool_wallet_config(
+    plot_nft: &PlotNft,
+    payout_address: &str,
+) -> Result<PoolWalletConfig, Error> {
+    Ok(PoolWalletConfig {
+        launcher_id: plot_nft.launcher_id,
+        pool_url: plot_nft.pool_state.pool_url.clone().unwrap_or_default(),
+        target_puzzle_hash: plot_nft.pool_state.target_puzzle_hash,
+        payout_instructions: payout_address.to_string(),
+        p2_singleton_puzzle_hash: launcher_id_to_p2_puzzle_hash(
+            &plot_nft.launcher_id,
+            plot_nft.delay_time as u64,
+            &plot_nft.delay_puzzle_hash,
+        )?,
+        owner_public_key: plot_nft.pool_state.owner_pubkey,
+        difficulty: None,
+    })
+}
@@ -1,8 +1,9 @@
 use bip39::Mnemonic;
 use dg_xch_core::blockchain::sized_bytes::{prep_hex_str, Bytes32};
+use dg_xch_core::plots::PlotNft;
 use dg_xch_keys::parse_payout_address;
 use dialoguer::theme::ColorfulTheme;
-use dialoguer::Input;
+use dialoguer::{Confirm, Input, MultiSelect, Password};
 use hex::decode;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -12,10 +13,20 @@ use std::io::{Error, ErrorKind};
 use std::net::IpAddr;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::Mutex;
+use zeroize::Zeroizing;
 
-pub fn prompt_for_plot_directories() -> Result<Vec<String>, Error> {
-    let mut dirs = HashSet::new();
-    let mut first = true;
+pub fn prompt_for_plot_directories(current: Option<Vec<String>>) -> Result<Vec<String>, Error> {
+    let mut dirs: HashSet<String> = current.unwrap_or_default().into_iter().collect();
+    let mut first = dirs.is_empty();
+    if !first {
+        let mut sorted: Vec<&String> = dirs.iter().collect();
+        sorted.sort();
+        println!("Current Plot Directories:");
+        for dir in sorted {
+            println!("  {dir}");
+        }
+    }
     while let Some(dir) = Input::<String>::with_theme(&ColorfulTheme::default())
         .with_prompt(if first {
             first = false;
@@ -47,43 +58,53 @@ pub fn prompt_for_plot_directories() -> Result<Vec<String>, Error> {
 }
 
 pub fn prompt_for_payout_address(current: Option<String>) -> Result<Bytes32, Error> {
-    let prompt = if let Some(current) = &current {
-        format!("Please Input XCH Payout Address, or leave blank to use {current}: ")
-    } else {
-        String::from("Please Input Your XCH Payout Address: ")
+    //An empty or invalid stored address can not be kept, the user has to enter a new one
+    let current_address = current
+        .as_deref()
+        .and_then(|c| parse_payout_address(c).ok());
+    let prompt = match (&current, &current_address) {
+        (Some(current), Some(_)) => {
+            format!("Please Input XCH Payout Address, or leave blank to use {current}: ")
+        }
+        (Some(current), None) if !current.trim().is_empty() => {
+            format!("Current Payout Address {current} is not valid, Please Input Your XCH Payout Address: ")
+        }
+        _ => String::from("Please Input Your XCH Payout Address: "),
     };
-    Input::<String>::with_theme(&ColorfulTheme::default())
+    let input = Input::<String>::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .allow_empty(true)
         .validate_with(|input: &String| -> Result<(), &str> {
-            if (input.trim().is_empty() && current.is_some()) || parse_payout_address(input).is_ok()
-            {
+            if input.trim().is_empty() {
+                if current_address.is_some() {
+                    Ok(())
+                } else {
+                    Err("There is no valid Payout Address to keep, Please input one.")
+                }
+            } else if parse_payout_address(input.trim()).is_ok() {
                 Ok(())
             } else {
                 Err("You did not input a valid XCH Address, Please try again.")
             }
         })
         .interact_text()
-        .map(|input| {
-            if input.trim().is_empty() && current.is_some() {
-                current
-                    .map(|v| {
-                        Bytes32::from(
-                            parse_payout_address(&v)
-                                .unwrap_or_else(|_| panic!("{input} Not a valid Payout Address")),
-                        )
-                    })
-                    .expect("Just Checked Is Some")
-            } else {
-                Bytes32::from(parse_payout_address(&input).expect("Checked In Validator"))
-            }
-        })
         .map_err(|e| {
             Error::new(
                 ErrorKind::InvalidInput,
                 format!("Failed to read user Input for XCH Address: {e:?}"),
             )
-        })
+        })?;
+    let address = if input.trim().is_empty() {
+        current_address.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "No valid XCH Payout Address was given",
+            )
+        })?
+    } else {
+        parse_payout_address(input.trim())?
+    };
+    Ok(Bytes32::from(address))
 }
 
 pub fn prompt_for_launcher_id(current: Option<Bytes32>) -> Result<Option<Bytes32>, Error> {
@@ -151,12 +172,8 @@ pub fn prompt_for_farming_fullnode(current: Option<String>) -> Result<String, Er
                 } else {
                     String::from("localhost")
                 }
-            } else if ["l", "localhost"].contains(&input.as_str()) {
-                String::from("localhost")
-            } else if ["c", "community"].contains(&input.as_str()) {
-                String::from("chia-proxy.evergreenminer-prod.com")
             } else {
-                input.trim().to_string()
+                resolve_fullnode_alias(&input)
             }
         })
         .map_err(|e| {
@@ -198,12 +215,8 @@ pub fn prompt_for_rpc_fullnode(current: Option<String>) -> Result<String, Error>
                 } else {
                     String::from("localhost")
                 }
-            } else if ["l", "localhost"].contains(&input.as_str()) {
-                String::from("localhost")
-            } else if ["c", "community"].contains(&input.as_str()) {
-                String::from("chia-proxy.evergreenminer-prod.com")
             } else {
-                input.trim().to_string()
+                resolve_fullnode_alias(&input)
             }
         })
         .map_err(|e| {
@@ -236,10 +249,8 @@ pub fn prompt_for_farming_port(current: Option<u16>) -> Result<u16, Error> {
         .map(|input| {
             if !input.trim().is_empty() {
                 u16::from_str(&input).expect("Was Validated in the validate_with call")
-            } else if let Some(current) = current {
-                current
             } else {
-                0 //Should Never Hit This
+                current.unwrap_or_default() //Validator ensures current is set here
             }
         })
         .map_err(|e| {
@@ -272,10 +283,8 @@ pub fn prompt_for_rpc_port(current: Option<u16>) -> Result<u16, Error> {
         .map(|input| {
             if !input.trim().is_empty() {
                 u16::from_str(&input).expect("Was Validated in the validate_with call")
-            } else if let Some(current) = current {
-                current
             } else {
-                0 //Should Never Hit This
+                current.unwrap_or_default() //Validator ensures current is set here
             }
         })
         .map_err(|e| {
@@ -323,43 +332,158 @@ pub fn prompt_for_ssl_path(current: Option<String>) -> Result<Option<String>, Er
         })
 }
 
+pub fn parse_mnemonic(input: &str) -> Result<Mnemonic, Error> {
+    Mnemonic::from_str(input.trim()).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Failed to parse Mnemonic: {e:?}"),
+        )
+    })
+}
+
 pub fn prompt_for_mnemonic<P: AsRef<Path>>(path: Option<P>) -> Result<Mnemonic, Error> {
     if let Some(mnemonic_file) = path {
-        Mnemonic::from_str(
-            &fs::read_to_string(mnemonic_file)
-                .map_err(|e| Error::new(e.kind(), format!("Failed to Mnemonic File: {e:?}")))?,
-        )
-        .map_err(|e| {
-            Error::new(
-                ErrorKind::InvalidInput,
-                format!("Failed to parse Mnemonic: {e:?}"),
-            )
-        })
+        parse_mnemonic(&Zeroizing::new(fs::read_to_string(mnemonic_file).map_err(
+            |e| Error::new(e.kind(), format!("Failed to Mnemonic File: {e:?}")),
+        )?))
     } else {
-        Mnemonic::from_str(
-            &Input::<String>::with_theme(&ColorfulTheme::default())
-                .with_prompt("Please Input Your Mnemonic: ")
+        //Masked so the words never end up on screen or in the terminal scrollback
+        parse_mnemonic(&Zeroizing::new(
+            Password::with_theme(&ColorfulTheme::default())
+                .with_prompt("Please Input Your Mnemonic (hidden): ")
                 .validate_with(|input: &String| -> Result<(), &str> {
-                    if Mnemonic::from_str(input).is_ok() {
+                    if Mnemonic::from_str(input.trim()).is_ok() {
                         Ok(())
                     } else {
                         Err("You did not input a valid Mnemonic, Please try again.")
                     }
                 })
-                .interact_text()
+                .interact()
                 .map_err(|e| {
                     Error::new(
                         ErrorKind::InvalidInput,
                         format!("Failed to read user Input for Mnemonic: {e:?}"),
                     )
                 })?,
+        ))
+    }
+}
+
+pub fn prompt_for_overwrite(path: &Path) -> Result<bool, Error> {
+    Confirm::new()
+        .with_prompt(format!(
+            "An existing config exists at {:?}, would you like to override it? (Y/N)",
+            path
+        ))
+        .interact()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Interrupted,
+                format!("Dialog Interrupted: {:?}", e),
+            )
+        })
+}
+
+pub fn prompt_for_passphrase() -> Result<String, Error> {
+    Password::with_theme(&ColorfulTheme::default())
+        .with_prompt("Please Input Your Mnemonic Passphrase, leave blank for none: ")
+        .allow_empty_password(true)
+        .interact()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Failed to read user Input for Passphrase: {e:?}"),
+            )
+        })
+}
+
+pub fn prompt_for_config_passphrase(confirm: bool) -> Result<String, Error> {
+    let theme = ColorfulTheme::default();
+    let mut prompt =
+        Password::with_theme(&theme).with_prompt("Please Input Your Config Passphrase: ");
+    if confirm {
+        prompt = prompt.with_confirmation(
+            "Repeat Your Config Passphrase: ",
+            "Passphrases do not match",
+        );
+    }
+    prompt.interact().map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Failed to read user Input for Config Passphrase: {e:?}"),
         )
+    })
+}
+
+pub fn prompt_for_bundle_passphrase(confirm: bool) -> Result<String, Error> {
+    let theme = ColorfulTheme::default();
+    let mut prompt =
+        Password::with_theme(&theme).with_prompt("Please Input Your Key Bundle Passphrase: ");
+    if confirm {
+        prompt = prompt.with_confirmation(
+            "Repeat Your Key Bundle Passphrase: ",
+            "Passphrases do not match",
+        );
+    }
+    prompt.interact().map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Failed to read user Input for Key Bundle Passphrase: {e:?}"),
+        )
+    })
+}
+
+pub fn prompt_for_another_key() -> Result<bool, Error> {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Would you like to add another key to this config?")
+        .default(false)
+        .interact()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Failed to read user Input for Another Key: {e:?}"),
+            )
+        })
+}
+
+pub fn prompt_for_plotnfts(plotnfts: &[PlotNft]) -> Result<Vec<Bytes32>, Error> {
+    let items: Vec<String> = plotnfts
+        .iter()
+        .map(|p| {
+            format!(
+                "{} ({})",
+                p.launcher_id,
+                p.pool_state.pool_url.as_deref().unwrap_or("Self Pooling")
+            )
+        })
+        .collect();
+    MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select the PlotNFTs to farm (space to toggle, enter to confirm)")
+        .items(&items)
+        .defaults(&vec![true; items.len()])
+        .interact()
+        .map(|selected| {
+            selected
+                .into_iter()
+                .map(|i| plotnfts[i].launcher_id)
+                .collect()
+        })
         .map_err(|e| {
             Error::new(
                 ErrorKind::InvalidInput,
-                format!("Failed to parse Mnemonic: {e:?}"),
+                format!("Failed to read user Input for PlotNFT Selection: {e:?}"),
             )
         })
+}
+
+pub fn resolve_fullnode_alias(input: &str) -> String {
+    let trimmed = input.trim();
+    if ["l", "localhost"].contains(&trimmed) {
+        String::from("localhost")
+    } else if ["c", "community"].contains(&trimmed) {
+        String::from("chia-proxy.evergreenminer-prod.com")
+    } else {
+        trimmed.to_string()
     }
 }
 
@@ -371,3 +495,344 @@ static DOMAIN_REGEX: Lazy<Regex> = Lazy::new(|| {
 fn is_domain(domain: &str) -> bool {
     DOMAIN_REGEX.is_match(domain)
 }
+
+//Source of answers for the generate and edit flows. Every method receives the value that would be
+//used if the user accepted the default, implementations decide whether to ask, answer or fail.
+pub trait PromptProvider: Send + Sync {
+    fn overwrite(&self, path: &Path) -> Result<bool, Error>;
+    fn mnemonic(&self) -> Result<Mnemonic, Error>;
+    //Asked after each typed in mnemonic, another mnemonic is requested while this returns true
+    fn another_key(&self) -> Result<bool, Error>;
+    fn passphrase(&self) -> Result<String, Error>;
+    //Passphrase protecting the encrypted farmer_info, confirm is set when encrypting
+    fn config_passphrase(&self, confirm: bool) -> Result<String, Error>;
+    //Passphrase protecting an exported key bundle, confirm is set when exporting
+    fn bundle_passphrase(&self, confirm: bool) -> Result<String, Error>;
+    fn payout_address(&self, current: Option<String>) -> Result<Bytes32, Error>;
+    fn farming_fullnode(&self, current: Option<String>) -> Result<String, Error>;
+    fn rpc_fullnode(&self, current: Option<String>) -> Result<String, Error>;
+    fn farming_port(&self, current: Option<u16>) -> Result<u16, Error>;
+    fn rpc_port(&self, current: Option<u16>) -> Result<u16, Error>;
+    fn ssl_path(&self, current: Option<String>) -> Result<Option<String>, Error>;
+    fn launcher_id(&self, current: Option<Bytes32>) -> Result<Option<Bytes32>, Error>;
+    fn plot_directories(&self, current: Option<Vec<String>>) -> Result<Vec<String>, Error>;
+    //Returns the launcher ids of the discovered PlotNFTs that should be farmed
+    fn select_plotnfts(&self, plotnfts: &[PlotNft]) -> Result<Vec<Bytes32>, Error>;
+}
+
+//Asks the user on the terminal
+#[derive(Default, Debug, Clone, Copy)]
+pub struct DialoguerPrompts;
+impl PromptProvider for DialoguerPrompts {
+    fn overwrite(&self, path: &Path) -> Result<bool, Error> {
+        prompt_for_overwrite(path)
+    }
+    fn mnemonic(&self) -> Result<Mnemonic, Error> {
+        prompt_for_mnemonic::<&Path>(None)
+    }
+    fn another_key(&self) -> Result<bool, Error> {
+        prompt_for_another_key()
+    }
+    fn passphrase(&self) -> Result<String, Error> {
+        prompt_for_passphrase()
+    }
+    fn config_passphrase(&self, confirm: bool) -> Result<String, Error> {
+        prompt_for_config_passphrase(confirm)
+    }
+    fn bundle_passphrase(&self, confirm: bool) -> Result<String, Error> {
+        prompt_for_bundle_passphrase(confirm)
+    }
+    fn payout_address(&self, current: Option<String>) -> Result<Bytes32, Error> {
+        prompt_for_payout_address(current)
+    }
+    fn farming_fullnode(&self, current: Option<String>) -> Result<String, Error> {
+        prompt_for_farming_fullnode(current)
+    }
+    fn rpc_fullnode(&self, current: Option<String>) -> Result<String, Error> {
+        prompt_for_rpc_fullnode(current)
+    }
+    fn farming_port(&self, current: Option<u16>) -> Result<u16, Error> {
+        prompt_for_farming_port(current)
+    }
+    fn rpc_port(&self, current: Option<u16>) -> Result<u16, Error> {
+        prompt_for_rpc_port(current)
+    }
+    fn ssl_path(&self, current: Option<String>) -> Result<Option<String>, Error> {
+        prompt_for_ssl_path(current)
+    }
+    fn launcher_id(&self, current: Option<Bytes32>) -> Result<Option<Bytes32>, Error> {
+        prompt_for_launcher_id(current)
+    }
+    fn plot_directories(&self, current: Option<Vec<String>>) -> Result<Vec<String>, Error> {
+        prompt_for_plot_directories(current)
+    }
+    fn select_plotnfts(&self, plotnfts: &[PlotNft]) -> Result<Vec<Bytes32>, Error> {
+        prompt_for_plotnfts(plotnfts)
+    }
+}
+
+//Answers every prompt from a set of canned values, falling back to the current value.
+//Prompts without an answer or current value fail instead of blocking.
+//Unknown keys are rejected so a misspelled answer does not silently fall back to the default.
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptAnswers {
+    pub overwrite: Option<bool>,
+    pub mnemonic: Option<String>,
+    pub passphrase: Option<String>,
+    pub config_passphrase: Option<String>,
+    pub bundle_passphrase: Option<String>,
+    pub payout_address: Option<String>,
+    pub fullnode_ws_host: Option<String>,
+    pub fullnode_ws_port: Option<u16>,
+    pub fullnode_rpc_host: Option<String>,
+    pub fullnode_rpc_port: Option<u16>,
+    pub ssl_path: Option<String>,
+    pub launcher_id: Option<Bytes32>,
+    pub plot_directories: Option<Vec<String>>,
+    //Launcher ids to farm when more than one PlotNFT is found, all are used when not set
+    pub plotnfts: Option<Vec<Bytes32>>,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct ScriptedPrompts {
+    pub answers: PromptAnswers,
+}
+impl ScriptedPrompts {
+    pub fn new(answers: PromptAnswers) -> Self {
+        Self { answers }
+    }
+    //Loads answers from a YAML file using the field names of PromptAnswers
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let answers = serde_yaml::from_str(
+            &fs::read_to_string(path.as_ref())
+                .map_err(|e| Error::new(e.kind(), format!("Failed to read Answer File: {e:?}")))?,
+        )
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Failed to parse Answer File: {e:?}"),
+            )
+        })?;
+        Ok(Self { answers })
+    }
+}
+fn no_answer(field: &str) -> Error {
+    Error::new(
+        ErrorKind::NotFound,
+        format!("No answer provided for {field}"),
+    )
+}
+impl PromptProvider for ScriptedPrompts {
+    fn overwrite(&self, _path: &Path) -> Result<bool, Error> {
+        self.answers.overwrite.ok_or_else(|| no_answer("overwrite"))
+    }
+    fn mnemonic(&self) -> Result<Mnemonic, Error> {
+        parse_mnemonic(
+            self.answers
+                .mnemonic
+                .as_ref()
+                .ok_or_else(|| no_answer("mnemonic"))?,
+        )
+    }
+    fn another_key(&self) -> Result<bool, Error> {
+        Ok(false)
+    }
+    fn passphrase(&self) -> Result<String, Error> {
+        self.answers
+            .passphrase
+            .clone()
+            .ok_or_else(|| no_answer("passphrase"))
+    }
+    fn config_passphrase(&self, _confirm: bool) -> Result<String, Error> {
+        self.answers
+            .config_passphrase
+            .clone()
+            .ok_or_else(|| no_answer("config_passphrase"))
+    }
+    fn bundle_passphrase(&self, _confirm: bool) -> Result<String, Error> {
+        self.answers
+            .bundle_passphrase
+            .clone()
+            .ok_or_else(|| no_answer("bundle_passphrase"))
+    }
+    fn payout_address(&self, current: Option<String>) -> Result<Bytes32, Error> {
+        let address = self
+            .answers
+            .payout_address
+            .clone()
+            .or(current)
+            .ok_or_else(|| no_answer("payout_address"))?;
+        Ok(Bytes32::from(parse_payout_address(&address)?))
+    }
+    fn farming_fullnode(&self, current: Option<String>) -> Result<String, Error> {
+        self.answers
+            .fullnode_ws_host
+            .clone()
+            .or(current)
+            .map(|h| resolve_fullnode_alias(&h))
+            .ok_or_else(|| no_answer("fullnode_ws_host"))
+    }
+    fn rpc_fullnode(&self, current: Option<String>) -> Result<String, Error> {
+        self.answers
+            .fullnode_rpc_host
+            .clone()
+            .or(current)
+            .map(|h| resolve_fullnode_alias(&h))
+            .ok_or_else(|| no_answer("fullnode_rpc_host"))
+    }
+    fn farming_port(&self, current: Option<u16>) -> Result<u16, Error> {
+        self.answers
+            .fullnode_ws_port
+            .or(current)
+            .ok_or_else(|| no_answer("fullnode_ws_port"))
+    }
+    fn rpc_port(&self, current: Option<u16>) -> Result<u16, Error> {
+        self.answers
+            .fullnode_rpc_port
+            .or(current)
+            .ok_or_else(|| no_answer("fullnode_rpc_port"))
+    }
+    fn ssl_path(&self, current: Option<String>) -> Result<Option<String>, Error> {
+        match self.answers.ssl_path.clone() {
+            Some(path) if !Path::new(&path).exists() => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("SSL Path does not exist: {path}"),
+            )),
+            Some(path) => Ok(Some(path)),
+            None => Ok(current),
+        }
+    }
+    fn launcher_id(&self, current: Option<Bytes32>) -> Result<Option<Bytes32>, Error> {
+        Ok(self.answers.launcher_id.or(current))
+    }
+    fn plot_directories(&self, current: Option<Vec<String>>) -> Result<Vec<String>, Error> {
+        Ok(self
+            .answers
+            .plot_directories
+            .clone()
+            .or(current)
+            .unwrap_or_default())
+    }
+    fn select_plotnfts(&self, plotnfts: &[PlotNft]) -> Result<Vec<Bytes32>, Error> {
+        Ok(plotnfts
+            .iter()
+            .map(|p| p.launcher_id)
+            .filter(|l| {
+                self.answers
+                    .plotnfts
+                    .as_ref()
+                    .map(|selected| selected.contains(l))
+                    .unwrap_or(true)
+            })
+            .collect())
+    }
+}
+
+//Used for --non-interactive, accepts every current value and never asks
+pub fn non_interactive_prompts() -> ScriptedPrompts {
+    ScriptedPrompts::default()
+}
+
+//Test double, answers like ScriptedPrompts and records the name of every prompt it was asked
+#[derive(Default, Debug)]
+pub struct MockPrompts {
+    inner: ScriptedPrompts,
+    asked: Mutex<Vec<&'static str>>,
+}
+impl MockPrompts {
+    pub fn new(answers: PromptAnswers) -> Self {
+        Self {
+            inner: ScriptedPrompts::new(answers),
+            asked: Mutex::new(vec![]),
+        }
+    }
+    pub fn asked(&self) -> Vec<&'static str> {
+        self.asked.lock().map(|a| a.clone()).unwrap_or_default()
+    }
+    fn record(&self, prompt: &'static str) {
+        if let Ok(mut asked) = self.asked.lock() {
+            asked.push(prompt);
+        }
+    }
+}
+impl PromptProvider for MockPrompts {
+    fn overwrite(&self, path: &Path) -> Result<bool, Error> {
+        self.record("overwrite");
+        self.inner.overwrite(path)
+    }
+    fn mnemonic(&self) -> Result<Mnemonic, Error> {
+        self.record("mnemonic");
+        self.inner.mnemonic()
+    }
+    fn another_key(&self) -> Result<bool, Error> {
+        self.record("another_key");
+        self.inner.another_key()
+    }
+    fn passphrase(&self) -> Result<String, Error> {
+        self.record("passphrase");
+        self.inner.passphrase()
+    }
+    fn config_passphrase(&self, confirm: bool) -> Result<String, Error> {
+        self.record("config_passphrase");
+        self.inner.config_passphrase(confirm)
+    }
+    fn bundle_passphrase(&self, confirm: bool) -> Result<String, Error> {
+        self.record("bundle_passphrase");
+        self.inner.bundle_passphrase(confirm)
+    }
+    fn payout_address(&self, current: Option<String>) -> Result<Bytes32, Error> {
+        self.record("payout_address");
+        self.inner.payout_address(current)
+    }
+    fn farming_fullnode(&self, current: Option<String>) -> Result<String, Error> {
+        self.record("fullnode_ws_host");
+        self.inner.farming_fullnode(current)
+    }
+    fn rpc_fullnode(&self, current: Option<String>) -> Result<String, Error> {
+        self.record("fullnode_rpc_host");
+        self.inner.rpc_fullnode(current)
+    }
+    fn farming_port(&self, current: Option<u16>) -> Result<u16, Error> {
+        self.record("fullnode_ws_port");
+        self.inner.farming_port(current)
+    }
+    fn rpc_port(&self, current: Option<u16>) -> Result<u16, Error> {
+        self.record("fullnode_rpc_port");
+        self.inner.rpc_port(current)
+    }
+    fn ssl_path(&self, current: Option<String>) -> Result<Option<String>, Error> {
+        self.record("ssl_path");
+        self.inner.ssl_path(current)
+    }
+    fn launcher_id(&self, current: Option<Bytes32>) -> Result<Option<Bytes32>, Error> {
+        self.record("launcher_id");
+        self.inner.launcher_id(current)
+    }
+    fn plot_directories(&self, current: Option<Vec<String>>) -> Result<Vec<String>, Error> {
+        self.record("plot_directories");
+        self.inner.plot_directories(current)
+    }
+    fn select_plotnfts(&self, plotnfts: &[PlotNft]) -> Result<Vec<Bytes32>, Error> {
+        self.record("plotnfts");
+        self.inner.select_plotnfts(plotnfts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_files_reject_unknown_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("answers.yaml");
+        fs::write(&path, "payout_address: xch1abc\nfullnode_ws_port: 8444\n").unwrap();
+        let prompts = ScriptedPrompts::from_file(&path).unwrap();
+        assert_eq!(prompts.answers.fullnode_ws_port, Some(8444));
+        fs::write(&path, "payout_adress: xch1abc\n").unwrap();
+        let error = ScriptedPrompts::from_file(&path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().contains("payout_adress"));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::config::Config;
+use crate::{CA_PRIVATE_CRT, PRIVATE_CRT, PRIVATE_KEY};
+use dg_xch_clients::api::full_node::FullnodeAPI;
+use dg_xch_clients::rpc::full_node::FullnodeClient;
+use dg_xch_clients::ClientSSLConfig;
+use log::info;
+use std::collections::HashMap;
+use std::io::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+pub fn rpc_client_from_config(
+    config: &Config,
+    headers: &Option<HashMap<String, String>>,
+) -> Arc<FullnodeClient> {
+    Arc::new(FullnodeClient::new(
+        &config.fullnode_rpc_host,
+        config.fullnode_rpc_port,
+        600,
+        if is_community_node(config) {
+            None
+        } else {
+            config.ssl_root_path.clone().map(|s| ClientSSLConfig {
+                ssl_crt_path: Path::new(&s)
+                    .join(PRIVATE_CRT)
+                    .to_string_lossy()
+                    .to_string(),
+                ssl_key_path: Path::new(&s)
+                    .join(PRIVATE_KEY)
+                    .to_string_lossy()
+                    .to_string(),
+                ssl_ca_crt_path: Path::new(&s)
+                    .join(CA_PRIVATE_CRT)
+                    .to_string_lossy()
+                    .to_string(),
+            })
+        },
+        headers,
+    ))
+}
+
+//Calls get_blockchain_state, the cheapest RPC that needs a working SSL setup
+pub async fn check_rpc_connection(
+    config: &Config,
+    headers: &Option<HashMap<String, String>>,
+) -> Result<(), Error> {
+    let state = rpc_client_from_config(config, headers)
+        .get_blockchain_state()
+        .await
+        .map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!(
+                    "RPC connection to {}:{} failed: {e}",
+                    config.fullnode_rpc_host, config.fullnode_rpc_port
+                ),
+            )
+        })?;
+    info!(
+        "Connected to {}:{}, peak height {}",
+        config.fullnode_rpc_host,
+        config.fullnode_rpc_port,
+        state
+            .peak
+            .map(|p| p.height.to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    );
+    Ok(())
+}
+
+pub fn is_community_node(config: &Config) -> bool {
+    [
+        "chia-proxy.evergreenminer-prod.com",
+        "chia-proxy.galactechs.com",
+        "chia-proxy-testnet11.evergreenminer-prod.com",
+        "chia-proxy-testnet11.galactechs.com",
+    ]
+    .contains(&config.fullnode_rpc_host.to_ascii_lowercase().trim())
+}
@@ -0,0 +1,92 @@
+use crate::keys::master_key_from_mnemonic;
+use crate::prompts::{parse_mnemonic, PromptProvider};
+use bip39::Mnemonic;
+use blst::min_pk::SecretKey;
+use std::env;
+use std::fs;
+use std::io::{stdin, Error, ErrorKind, Read};
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+//Where a secret value such as the mnemonic or BIP39 passphrase is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretSource {
+    Prompt,
+    File(PathBuf),
+    Env(String),
+    Stdin,
+}
+impl SecretSource {
+    //Reads the secret, prompt is only called for the Prompt source
+    //The returned value is wiped from memory when dropped
+    pub fn read(
+        &self,
+        prompt: impl FnOnce() -> Result<String, Error>,
+    ) -> Result<Zeroizing<String>, Error> {
+        let mut secret = Zeroizing::new(match self {
+            SecretSource::Prompt => prompt()?,
+            SecretSource::File(path) => fs::read_to_string(path)
+                .map_err(|e| Error::new(e.kind(), format!("Failed to read {path:?}: {e:?}")))?,
+            SecretSource::Stdin => {
+                let mut secret = String::new();
+                stdin().read_to_string(&mut secret)?;
+                secret
+            }
+            SecretSource::Env(name) => env::var(name).map_err(|e| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!("Failed to read environment variable {name}: {e:?}"),
+                )
+            })?,
+        });
+        //Trim in place so no untrimmed copy is left behind
+        let trimmed = secret.trim_end_matches(['\r', '\n']).len();
+        secret.truncate(trimmed);
+        Ok(secret)
+    }
+}
+
+pub fn read_mnemonic(
+    source: &SecretSource,
+    prompts: &dyn PromptProvider,
+) -> Result<Mnemonic, Error> {
+    match source {
+        SecretSource::Prompt => prompts.mnemonic(),
+        source => parse_mnemonic(&source.read(|| Err(Error::other("Not a prompt source")))?),
+    }
+}
+
+//Derives the master key of a mnemonic, without a passphrase source the empty passphrase is used
+pub fn derive_master_key(
+    mnemonic: &Mnemonic,
+    passphrase: Option<&SecretSource>,
+    prompts: &dyn PromptProvider,
+) -> Result<SecretKey, Error> {
+    let passphrase = match passphrase {
+        Some(source) => source.read(|| prompts.passphrase())?,
+        None => Zeroizing::new(String::new()),
+    };
+    master_key_from_mnemonic(mnemonic, &passphrase)
+}
+
+//Reads the passphrase protecting an encrypted farmer_info, prompting when no source is given
+pub fn read_config_passphrase(
+    source: Option<&SecretSource>,
+    confirm: bool,
+    prompts: &dyn PromptProvider,
+) -> Result<Zeroizing<String>, Error> {
+    source
+        .unwrap_or(&SecretSource::Prompt)
+        .read(|| prompts.config_passphrase(confirm))
+}
+
+//Reads the passphrase protecting an exported key bundle, prompting when no source is given
+pub fn read_bundle_passphrase(
+    source: Option<&SecretSource>,
+    confirm: bool,
+    prompts: &dyn PromptProvider,
+) -> Result<Zeroizing<String>, Error> {
+    source
+        .unwrap_or(&SecretSource::Prompt)
+        .read(|| prompts.bundle_passphrase(confirm))
+}
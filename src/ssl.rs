@@ -0,0 +1,327 @@
+use crate::backup::backup_directory;
+use crate::config::write_private_file;
+use crate::validate::ValidationIssue;
+use crate::{CA_PRIVATE_CRT, CA_PRIVATE_KEY, PRIVATE_CRT, PRIVATE_KEY};
+use dg_xch_core::ssl::{create_all_ssl, generate_ca_signed_cert};
+use log::info;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1v15::{Signature, VerifyingKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::sha2::Sha256;
+use rsa::signature::Verifier;
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use std::fs;
+use std::io::{Error, ErrorKind};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use x509_cert::der::{DecodePem, Encode};
+use x509_cert::Certificate;
+use zeroize::Zeroizing;
+
+//The CA files Chia keeps in ssl/ca, the private CA signs the farmer certs
+static CA_FILES: [&str; 4] = [
+    "private_ca.crt",
+    "private_ca.key",
+    "chia_ca.crt",
+    "chia_ca.key",
+];
+//sha256WithRSAEncryption, the only signature Chia and dg_xch use for their certs
+const SHA256_WITH_RSA: &str = "1.2.840.113549.1.1.11";
+
+pub fn load_certificate(path: &Path) -> Result<Certificate, Error> {
+    let pem = fs::read(path)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to read {path:?}: {e}")))?;
+    Certificate::from_pem(&pem).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to parse certificate {path:?}: {e}"),
+        )
+    })
+}
+
+//Checks the issuer name and the signature of cert against the public key of ca
+pub fn verify_signed_by(cert: &Certificate, ca: &Certificate) -> Result<(), Error> {
+    if cert.tbs_certificate.issuer != ca.tbs_certificate.subject {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Certificate is issued by {} not by {}",
+                cert.tbs_certificate.issuer, ca.tbs_certificate.subject
+            ),
+        ));
+    }
+    if cert.signature_algorithm.oid.to_string() != SHA256_WITH_RSA {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "Unsupported signature algorithm {}",
+                cert.signature_algorithm.oid
+            ),
+        ));
+    }
+    let to_error = |e: String| Error::new(ErrorKind::InvalidData, e);
+    let ca_key = public_key_of(ca)?;
+    let tbs = cert
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| to_error(format!("Invalid certificate: {e}")))?;
+    let signature = Signature::try_from(cert.signature.raw_bytes())
+        .map_err(|e| to_error(format!("Invalid certificate signature: {e}")))?;
+    VerifyingKey::<Sha256>::new(ca_key)
+        .verify(&tbs, &signature)
+        .map_err(|_| to_error("Certificate signature does not match the CA".to_string()))
+}
+
+//Parsed details of one certificate under the ssl root, printed by `ssl check`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateReport {
+    pub path: PathBuf,
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub expired: bool,
+    pub key_path: PathBuf,
+    //None when the key is missing or can not be read
+    pub key_matches: Option<bool>,
+}
+
+pub fn public_key_of(cert: &Certificate) -> Result<RsaPublicKey, Error> {
+    let der = cert
+        .tbs_certificate
+        .subject_public_key_info
+        .to_der()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("Invalid public key: {e}")))?;
+    RsaPublicKey::from_public_key_der(&der)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("Invalid public key: {e}")))
+}
+
+//Chia writes PKCS#1 keys, PKCS#8 is accepted as well
+pub fn load_private_key(path: &Path) -> Result<RsaPrivateKey, Error> {
+    let pem = Zeroizing::new(
+        fs::read_to_string(path)
+            .map_err(|e| Error::new(e.kind(), format!("Failed to read {path:?}: {e}")))?,
+    );
+    RsaPrivateKey::from_pkcs1_pem(&pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs8_pem(&pem))
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Failed to parse private key {path:?}: {e}"),
+            )
+        })
+}
+
+fn check_certificate(
+    ssl_root: &Path,
+    crt: &str,
+    key: &str,
+    issues: &mut Vec<ValidationIssue>,
+) -> Option<(Certificate, CertificateReport)> {
+    let path = ssl_root.join(crt);
+    let key_path = ssl_root.join(key);
+    let issue = |path: &Path, message: String| {
+        ValidationIssue::new(path.to_string_lossy().to_string(), message)
+    };
+    if !path.is_file() {
+        issues.push(issue(&path, "Missing certificate".to_string()));
+        return None;
+    }
+    let cert = match load_certificate(&path) {
+        Ok(cert) => cert,
+        Err(e) => {
+            issues.push(issue(&path, e.to_string()));
+            return None;
+        }
+    };
+    let validity = &cert.tbs_certificate.validity;
+    let now = SystemTime::now();
+    let expired = validity.not_after.to_system_time() < now;
+    if expired {
+        issues.push(issue(&path, format!("Expired on {}", validity.not_after)));
+    } else if validity.not_before.to_system_time() > now {
+        issues.push(issue(
+            &path,
+            format!("Not valid before {}", validity.not_before),
+        ));
+    }
+    let key_matches = if !key_path.is_file() {
+        issues.push(issue(&key_path, "Missing private key".to_string()));
+        None
+    } else {
+        match (load_private_key(&key_path), public_key_of(&cert)) {
+            (Ok(private_key), Ok(public_key)) => {
+                let matches = private_key.to_public_key() == public_key;
+                if !matches {
+                    issues.push(issue(
+                        &key_path,
+                        format!("Private key does not belong to {path:?}"),
+                    ));
+                }
+                Some(matches)
+            }
+            (Err(e), _) => {
+                issues.push(issue(&key_path, e.to_string()));
+                None
+            }
+            (_, Err(e)) => {
+                issues.push(issue(&path, e.to_string()));
+                None
+            }
+        }
+    };
+    let report = CertificateReport {
+        path,
+        subject: cert.tbs_certificate.subject.to_string(),
+        issuer: cert.tbs_certificate.issuer.to_string(),
+        not_before: validity.not_before.to_string(),
+        not_after: validity.not_after.to_string(),
+        expired,
+        key_path,
+        key_matches,
+    };
+    Some((cert, report))
+}
+
+//Inspects the CA and farmer certs the RPC client uses, reporting every problem with the file path
+pub fn check_ssl(ssl_root: &Path) -> (Vec<CertificateReport>, Vec<ValidationIssue>) {
+    let mut issues = vec![];
+    let mut reports = vec![];
+    let ca = check_certificate(ssl_root, CA_PRIVATE_CRT, CA_PRIVATE_KEY, &mut issues);
+    let farmer = check_certificate(ssl_root, PRIVATE_CRT, PRIVATE_KEY, &mut issues);
+    if let (Some((ca, _)), Some((farmer, _))) = (&ca, &farmer) {
+        if let Err(e) = verify_signed_by(farmer, ca) {
+            issues.push(ValidationIssue::new(
+                ssl_root.join(PRIVATE_CRT).to_string_lossy().to_string(),
+                format!("Not signed by {:?}: {e}", ssl_root.join(CA_PRIVATE_CRT)),
+            ));
+        }
+    }
+    reports.extend(ca.map(|(_, report)| report));
+    reports.extend(farmer.map(|(_, report)| report));
+    (reports, issues)
+}
+
+//Accepts a Chia root, its ssl directory or the ssl/ca directory itself
+pub fn locate_chia_ca(path: &Path) -> Result<PathBuf, Error> {
+    [
+        path.join("config/ssl/ca"),
+        path.join("ssl/ca"),
+        path.join("ca"),
+        path.to_path_buf(),
+    ]
+    .into_iter()
+    .find(|dir| dir.join("private_ca.crt").exists() && dir.join("private_ca.key").exists())
+    .ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("No Chia CA (private_ca.crt and private_ca.key) found in {path:?}"),
+        )
+    })
+}
+
+fn copy_ca(chia_ca: &Path, ssl_root: &Path) -> Result<(), Error> {
+    let ca_dir = ssl_root.join("ca");
+    fs::create_dir_all(&ca_dir)?;
+    for name in CA_FILES {
+        let source = chia_ca.join(name);
+        //Older installs lack chia_ca, create_all_ssl writes the well known one
+        if source.exists() {
+            write_private_file(&ca_dir.join(name), &fs::read(&source)?)?;
+            info!("Copied {source:?} to {:?}", ca_dir.join(name));
+        }
+    }
+    Ok(())
+}
+
+fn verify_farmer_certificate(ssl_root: &Path) -> Result<(), Error> {
+    verify_signed_by(
+        &load_certificate(&ssl_root.join(PRIVATE_CRT))?,
+        &load_certificate(&ssl_root.join(CA_PRIVATE_CRT))?,
+    )
+    .map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!(
+                "{:?} does not chain to the CA: {e}",
+                ssl_root.join(PRIVATE_CRT)
+            ),
+        )
+    })?;
+    info!("Farmer certificate in {ssl_root:?} is signed by the CA");
+    Ok(())
+}
+
+//Copies the Chia CA into ssl_root, generates the node certs signed by it
+//and checks that the farmer cert chains to the copied CA
+pub fn setup_ssl(chia_path: &Path, ssl_root: &Path) -> Result<(), Error> {
+    let chia_ca = locate_chia_ca(chia_path)?;
+    //Certs already in ssl_root were signed by the existing CA and would not be replaced
+    let existing_ca = ssl_root.join(CA_PRIVATE_CRT);
+    if existing_ca.exists() && fs::read(&existing_ca)? != fs::read(chia_ca.join("private_ca.crt"))?
+    {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{ssl_root:?} already contains a different CA, use ssl regenerate --ca to replace it"),
+        ));
+    }
+    copy_ca(&chia_ca, ssl_root)?;
+    create_all_ssl(ssl_root, false)?;
+    restrict_private_keys(ssl_root)?;
+    verify_farmer_certificate(ssl_root)
+}
+
+//create_all_ssl writes the node keys with the umask permissions, limit every key to the owner
+fn restrict_private_keys(dir: &Path) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            restrict_private_keys(&path)?;
+        } else if path.extension().is_some_and(|e| e == "key") {
+            #[cfg(unix)]
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    Ok(())
+}
+
+//Backs up ssl_root, optionally replaces its CA and signs a new farmer cert with the CA.
+//Only the farmer cert is used to connect, the other node certs are left as they are
+pub fn regenerate_ssl(
+    ssl_root: &Path,
+    new_ca: Option<&Path>,
+    backup_retention: usize,
+) -> Result<Option<PathBuf>, Error> {
+    //The certs are replaced in place, so a backup is taken even when backups are disabled,
+    //the retention only decides how many older backups are pruned
+    let backup_path = backup_directory(ssl_root, backup_retention.max(1))?;
+    if let Some(backup_path) = &backup_path {
+        info!("Backed up {ssl_root:?} to {backup_path:?}");
+    }
+    if let Some(new_ca) = new_ca {
+        copy_ca(&locate_chia_ca(new_ca)?, ssl_root)?;
+    }
+    let ca_crt = fs::read(ssl_root.join(CA_PRIVATE_CRT))?;
+    let ca_key = Zeroizing::new(fs::read(ssl_root.join(CA_PRIVATE_KEY))?);
+    let crt_path = ssl_root.join(PRIVATE_CRT);
+    if let Some(parent) = crt_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    generate_ca_signed_cert(
+        &crt_path,
+        &ca_crt,
+        &ssl_root.join(PRIVATE_KEY),
+        &ca_key,
+        true,
+    )?;
+    #[cfg(unix)]
+    fs::set_permissions(
+        ssl_root.join(PRIVATE_KEY),
+        fs::Permissions::from_mode(0o600),
+    )?;
+    info!("Generated a new farmer certificate in {ssl_root:?}");
+    verify_farmer_certificate(ssl_root)?;
+    Ok(backup_path)
+}
@@ -0,0 +1,39 @@
+//Builders shared by the unit tests. Keys, launcher ids and pools are all made from a single
+//repeated byte, so a test can refer to the same value by number in every module
+use crate::config::{FarmingInfo, SecretBytes32};
+use dg_xch_core::blockchain::sized_bytes::{Bytes32, SizedBytes};
+use dg_xch_core::config::PoolWalletConfig;
+
+pub const PAYOUT: &str = "0x7777777777777777777777777777777777777777777777777777777777777777";
+
+pub fn bytes32(byte: u8) -> Bytes32 {
+    Bytes32::new(&[byte; 32])
+}
+
+pub fn secret_key(byte: u8) -> SecretBytes32 {
+    SecretBytes32::from(bytes32(byte))
+}
+
+//The farmer key also serves as pool key, owner and auth keys are left for the test to set
+pub fn farming_info(key: u8, launcher_id: Option<u8>) -> FarmingInfo {
+    FarmingInfo {
+        farmer_secret_key: secret_key(key),
+        launcher_id: launcher_id.map(bytes32),
+        pool_secret_key: Some(secret_key(key)),
+        owner_secret_key: None,
+        auth_secret_key: None,
+    }
+}
+
+//Paying out to PAYOUT, tests needing another pool_url or payout_instructions override them
+pub fn pool(launcher_id: u8) -> PoolWalletConfig {
+    PoolWalletConfig {
+        launcher_id: bytes32(launcher_id),
+        pool_url: "https://pool.example.com".to_string(),
+        target_puzzle_hash: Default::default(),
+        payout_instructions: PAYOUT.to_string(),
+        p2_singleton_puzzle_hash: Default::default(),
+        owner_public_key: Default::default(),
+        difficulty: None,
+    }
+}
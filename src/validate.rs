@@ -0,0 +1,237 @@
+use crate::config::Config;
+use crate::{get_ssl_root_path, CA_PRIVATE_CRT, CA_PRIVATE_KEY, PRIVATE_CRT, PRIVATE_KEY};
+use dg_xch_core::consensus::constants::CONSENSUS_CONSTANTS_MAP;
+use dg_xch_keys::parse_payout_address;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub path: String,
+    pub message: String,
+}
+impl ValidationIssue {
+    pub(crate) fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+//Runs every check against a loaded config, returning all problems found instead of stopping at the first
+pub fn validate_config(config: &Config) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+    if parse_payout_address(&config.payout_address).is_err() {
+        issues.push(ValidationIssue::new(
+            "payout_address",
+            format!("{:?} is not a valid XCH address", config.payout_address),
+        ));
+    }
+    if !CONSENSUS_CONSTANTS_MAP.contains_key(&config.selected_network) {
+        issues.push(ValidationIssue::new(
+            "selected_network",
+            format!("Unknown network {:?}", config.selected_network),
+        ));
+    }
+    for (index, pool) in config.pool_info.iter().enumerate() {
+        let path = format!("pool_info[{index}].launcher_id");
+        match config
+            .farmer_info
+            .iter()
+            .find(|f| f.launcher_id == Some(pool.launcher_id))
+        {
+            None => issues.push(ValidationIssue::new(
+                path,
+                format!("No farmer_info entry for launcher {}", pool.launcher_id),
+            )),
+            Some(info) => {
+                if info.owner_secret_key.is_none() {
+                    issues.push(ValidationIssue::new(
+                        path.clone(),
+                        format!(
+                            "farmer_info entry for launcher {} has no owner_secret_key",
+                            pool.launcher_id
+                        ),
+                    ));
+                }
+                if info.auth_secret_key.is_none() {
+                    issues.push(ValidationIssue::new(
+                        path,
+                        format!(
+                            "farmer_info entry for launcher {} has no auth_secret_key",
+                            pool.launcher_id
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    if let Some(gigahorse) = &config.harvester_configs.gigahorse {
+        check_plot_directories(
+            &mut issues,
+            "harvester_configs.gigahorse.plot_directories",
+            &gigahorse.plot_directories,
+        );
+    }
+    if let Some(druid_garden) = &config.harvester_configs.druid_garden {
+        check_plot_directories(
+            &mut issues,
+            "harvester_configs.druid_garden.plot_directories",
+            &druid_garden.plot_directories,
+        );
+    }
+    let ssl_root_path = get_ssl_root_path(config);
+    for file in [CA_PRIVATE_CRT, CA_PRIVATE_KEY, PRIVATE_CRT, PRIVATE_KEY] {
+        if !ssl_root_path.join(file).is_file() {
+            issues.push(ValidationIssue::new(
+                "ssl_root_path",
+                format!("Missing SSL file {:?}", ssl_root_path.join(file)),
+            ));
+        }
+    }
+    issues
+}
+
+fn check_plot_directories(issues: &mut Vec<ValidationIssue>, path: &str, directories: &[String]) {
+    for (index, directory) in directories.iter().enumerate() {
+        if !Path::new(directory).is_dir() {
+            issues.push(ValidationIssue::new(
+                format!("{path}[{index}]"),
+                format!("Plot directory {directory:?} does not exist"),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DruidGardenHarvesterConfig, FarmingInfo};
+    use crate::test_util::{farming_info, pool, secret_key, PAYOUT};
+    use std::fs;
+
+    fn pooling(launcher_id: u8, owner: bool, auth: bool) -> FarmingInfo {
+        FarmingInfo {
+            owner_secret_key: owner.then(|| secret_key(1)),
+            auth_secret_key: auth.then(|| secret_key(1)),
+            ..farming_info(1, Some(launcher_id))
+        }
+    }
+
+    //A config without problems, the ssl files and plot directory live in dir
+    fn valid_config(dir: &Path) -> Config {
+        for file in [CA_PRIVATE_CRT, CA_PRIVATE_KEY, PRIVATE_CRT, PRIVATE_KEY] {
+            let path = dir.join("ssl").join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let mut config = Config {
+            payout_address: PAYOUT.to_string(),
+            ssl_root_path: Some(dir.join("ssl").to_string_lossy().to_string()),
+            farmer_info: vec![pooling(10, true, true)],
+            pool_info: vec![pool(10)],
+            ..Default::default()
+        };
+        config
+            .harvester_configs
+            .gigahorse
+            .as_mut()
+            .unwrap()
+            .plot_directories = vec![dir.to_string_lossy().to_string()];
+        config
+    }
+
+    fn issue_paths(config: &Config) -> Vec<String> {
+        validate_config(config)
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect()
+    }
+
+    #[test]
+    fn valid_config_has_no_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(validate_config(&valid_config(dir.path())), vec![]);
+    }
+
+    #[test]
+    fn reports_payout_address_and_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            payout_address: "xch1invalid".to_string(),
+            selected_network: "not-a-network".to_string(),
+            ..valid_config(dir.path())
+        };
+        assert_eq!(
+            issue_paths(&config),
+            vec!["payout_address", "selected_network"]
+        );
+    }
+
+    #[test]
+    fn reports_pool_info_without_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            farmer_info: vec![pooling(10, false, true), pooling(11, true, false)],
+            pool_info: vec![pool(10), pool(11), pool(12)],
+            ..valid_config(dir.path())
+        };
+        let issues = validate_config(&config);
+        assert_eq!(
+            issues.iter().map(|i| i.path.as_str()).collect::<Vec<_>>(),
+            vec![
+                "pool_info[0].launcher_id",
+                "pool_info[1].launcher_id",
+                "pool_info[2].launcher_id"
+            ]
+        );
+        assert!(issues[0].message.contains("no owner_secret_key"));
+        assert!(issues[1].message.contains("no auth_secret_key"));
+        assert!(issues[2].message.contains("No farmer_info entry"));
+    }
+
+    #[test]
+    fn reports_missing_plot_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = valid_config(dir.path());
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        config
+            .harvester_configs
+            .gigahorse
+            .as_mut()
+            .unwrap()
+            .plot_directories
+            .push(missing.clone());
+        config.harvester_configs.druid_garden = Some(DruidGardenHarvesterConfig {
+            plot_directories: vec![missing],
+        });
+        assert_eq!(
+            issue_paths(&config),
+            vec![
+                "harvester_configs.gigahorse.plot_directories[1]",
+                "harvester_configs.druid_garden.plot_directories[0]"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_every_missing_ssl_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = valid_config(dir.path());
+        fs::remove_file(dir.path().join("ssl").join(PRIVATE_KEY)).unwrap();
+        fs::remove_file(dir.path().join("ssl").join(CA_PRIVATE_CRT)).unwrap();
+        let issues = validate_config(&config);
+        assert_eq!(
+            issues.iter().map(|i| i.path.as_str()).collect::<Vec<_>>(),
+            vec!["ssl_root_path", "ssl_root_path"]
+        );
+        assert!(issues[0].message.contains(CA_PRIVATE_CRT));
+        assert!(issues[1].message.contains(PRIVATE_KEY));
+    }
+}